@@ -0,0 +1,146 @@
+//! Debouncing of analog inputs, e.g. capacitive touch pads
+
+use crate::{BtnState, PinDebouncer, Ticks};
+
+/// Turns the samples of an analog channel into button states. A sample reaching the threshold
+/// counts as a press and it only counts as a release once it drops below the threshold minus the
+/// hysteresis, so a noisy reading close to the threshold doesn't toggle. The resulting levels
+/// are debounced by a `PinDebouncer`, with the same timings
+///
+/// An optional second, higher, threshold set by `set_firm_threshold` tells a light touch from a
+/// firm press, the latter being reported as `BtnState::FirmPressed`
+#[derive(Debug)]
+pub struct AnalogDebouncer<T: Ticks = u32> {
+    touch: PinDebouncer<T>,
+    firm: PinDebouncer<T>,
+    threshold: u16,
+    firm_threshold: Option<u16>,
+    hysteresis: u16,
+    touched: bool,
+    pressed_firmly: bool,
+}
+
+impl AnalogDebouncer {
+    /// Returns an AnalogDebouncer struct
+    ///
+    /// # Arguments
+    ///
+    /// * `press_ticks`, `repeat_ticks`, `hold_ticks` - Same as in `PinDebouncer::new`
+    /// * `threshold` - The sample value at which the input is considered to be pressed
+    /// * `hysteresis` - How far below a threshold the sample must drop to be released
+    pub const fn new(
+        press_ticks: u32,
+        repeat_ticks: u32,
+        hold_ticks: u32,
+        threshold: u16,
+        hysteresis: u16,
+    ) -> AnalogDebouncer {
+        AnalogDebouncer {
+            touch: PinDebouncer::new(press_ticks, repeat_ticks, hold_ticks),
+            firm: PinDebouncer::new(press_ticks, repeat_ticks, hold_ticks),
+            threshold,
+            firm_threshold: None,
+            hysteresis,
+            touched: false,
+            pressed_firmly: false,
+        }
+    }
+}
+
+impl<T: Ticks> AnalogDebouncer<T> {
+    /// Same as `new`, but counting the ticks with any `Ticks` type, see
+    /// `PinDebouncer::with_ticks`
+    pub fn with_ticks(
+        press_ticks: T,
+        repeat_ticks: T,
+        hold_ticks: T,
+        threshold: u16,
+        hysteresis: u16,
+    ) -> AnalogDebouncer<T> {
+        AnalogDebouncer {
+            touch: PinDebouncer::with_ticks(press_ticks, repeat_ticks, hold_ticks),
+            firm: PinDebouncer::with_ticks(press_ticks, repeat_ticks, hold_ticks),
+            threshold,
+            firm_threshold: None,
+            hysteresis,
+            touched: false,
+            pressed_firmly: false,
+        }
+    }
+
+    /// Sets the sample value at which a press is considered to be firm, it should be higher than
+    /// the threshold given in `new`. The same hysteresis applies. No firm press is reported until
+    /// this is set
+    pub fn set_firm_threshold(&mut self, value: u16) {
+        self.firm_threshold = Some(value);
+    }
+
+    fn crossed(&self, sample: u16, threshold: u16, above: bool) -> bool {
+        if above {
+            sample > threshold.saturating_sub(self.hysteresis)
+        } else {
+            sample >= threshold
+        }
+    }
+
+    /// Feeds a new sample of the analog channel, this method should be called every tick.
+    /// Returns true when this call completed a window, see `PinDebouncer::update`
+    pub fn update(&mut self, sample: u16) -> bool {
+        self.touched = self.crossed(sample, self.threshold, self.touched);
+        self.pressed_firmly = match self.firm_threshold {
+            Some(threshold) => self.crossed(sample, threshold, self.pressed_firmly),
+            None => false,
+        };
+        // Both debouncers share the same window, so they complete it at the same call
+        self.firm.update(self.pressed_firmly) | self.touch.update(self.touched)
+    }
+
+    /// Returns the debounced state, see `PinDebouncer::get_state`. A firm press is reported as
+    /// `FirmPressed` instead of `Pressed`, the other states take precedence over it
+    pub fn get_state(&mut self) -> BtnState {
+        let firm = self.firm.get_state();
+        match self.touch.get_state() {
+            BtnState::Pressed if firm != BtnState::UnPressed => BtnState::FirmPressed,
+            state => state,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn analog_firm_press() {
+        let mut analog_debouncer = AnalogDebouncer::new(4, 20, 100, 1000, 100);
+        analog_debouncer.set_firm_threshold(3000);
+
+        for _ in 0..4 {
+            analog_debouncer.update(1000);
+        }
+        assert_eq!(BtnState::ChangedToPressed, analog_debouncer.get_state());
+        for _ in 0..4 {
+            analog_debouncer.update(910);
+        }
+        assert_eq!(BtnState::Pressed, analog_debouncer.get_state());
+
+        for _ in 0..4 {
+            analog_debouncer.update(3000);
+        }
+        assert_eq!(BtnState::FirmPressed, analog_debouncer.get_state());
+        for _ in 0..4 {
+            analog_debouncer.update(2950);
+        }
+        assert_eq!(BtnState::FirmPressed, analog_debouncer.get_state());
+        for _ in 0..4 {
+            analog_debouncer.update(2000);
+        }
+        assert_eq!(BtnState::Pressed, analog_debouncer.get_state());
+
+        for _ in 0..4 {
+            analog_debouncer.update(900);
+        }
+        assert_eq!(BtnState::UnPressed, analog_debouncer.get_state());
+    }
+}
@@ -0,0 +1,89 @@
+//! Container of independent pin debouncers
+
+use crate::{BtnState, Error, PinDebouncer, Ticks};
+#[allow(deprecated)]
+use generic_array::{ArrayLength, GenericArray};
+
+/// Groups `N` independent `PinDebouncer`s, each one with its own timings, so they can be fed and
+/// queried by index from a single object. This is more flexible than a `PortDebouncer` when each
+/// button needs distinct timings, at the cost of a whole `PinDebouncer` per button
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct PinDebouncerArray<N: ArrayLength<PinDebouncer<T>>, T: Ticks = u32> {
+    debouncers: GenericArray<PinDebouncer<T>, N>,
+}
+
+#[allow(deprecated)]
+impl<N: ArrayLength<PinDebouncer<T>>, T: Ticks> PinDebouncerArray<N, T> {
+    /// Returns a PinDebouncerArray struct
+    ///
+    /// # Arguments
+    ///
+    /// * `debouncers` - The debouncer of each button, from index zero up
+    pub fn new(debouncers: GenericArray<PinDebouncer<T>, N>) -> PinDebouncerArray<N, T> {
+        PinDebouncerArray { debouncers }
+    }
+
+    /// Feeds a new sample of a single button, see `PinDebouncer::update`. Returns
+    /// `Error::BtnUninitialized` if `index` isn't below `N`
+    pub fn update(&mut self, index: usize, pressed: bool) -> Result<bool, Error> {
+        Ok(self.debouncer_mut(index)?.update(pressed))
+    }
+
+    /// Feeds a new sample of all the buttons at once, `values` holding one level per button from
+    /// index zero up. Returns `Error::LengthMismatch` if its length isn't `N`, leaving the
+    /// debouncers untouched
+    pub fn update_all(&mut self, values: &[bool]) -> Result<(), Error> {
+        if values.len() != N::USIZE {
+            return Err(Error::LengthMismatch);
+        }
+        for (debouncer, &pressed) in self.debouncers.iter_mut().zip(values) {
+            let _ = debouncer.update(pressed);
+        }
+        Ok(())
+    }
+
+    /// Returns the state of a button, see `PinDebouncer::get_state`. Returns
+    /// `Error::BtnUninitialized` if `index` isn't below `N`
+    pub fn get_state(&mut self, index: usize) -> Result<BtnState, Error> {
+        Ok(self.debouncer_mut(index)?.get_state())
+    }
+
+    /// Returns the debouncer of a button, e.g. to change its configuration. Returns
+    /// `Error::BtnUninitialized` if `index` isn't below `N`
+    pub fn debouncer_mut(&mut self, index: usize) -> Result<&mut PinDebouncer<T>, Error> {
+        self.debouncers
+            .get_mut(index)
+            .ok_or(Error::BtnUninitialized)
+    }
+}
+
+#[cfg(test)]
+#[allow(deprecated)]
+mod tests {
+
+    use super::*;
+    use generic_array::typenum::U2;
+
+    #[test]
+    fn array_distinct_timings() {
+        let mut pins: PinDebouncerArray<U2> = PinDebouncerArray::new(GenericArray::from([
+            PinDebouncer::new(2, 100, 100),
+            PinDebouncer::new(4, 100, 100),
+        ]));
+        for _ in 0..2 {
+            pins.update_all(&[true, true]).unwrap();
+        }
+        assert_eq!(BtnState::ChangedToPressed, pins.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, pins.get_state(1).unwrap());
+
+        for _ in 0..2 {
+            assert!(pins.update(1, true).is_ok());
+        }
+        assert_eq!(BtnState::ChangedToPressed, pins.get_state(1).unwrap());
+
+        assert!(pins.update(2, true).is_err());
+        assert!(pins.get_state(2).is_err());
+        assert!(pins.update_all(&[true]).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Clock driven debouncing for tickless firmware
+
+use crate::{BtnState, Error, PinDebouncer, Ticks};
+
+/// Monotonic time source used by `ClockedPinDebouncer`, e.g. a free running hardware timer. The
+/// unit is up to the implementation, but it must match the `tick_period` given to the debouncer.
+/// The value is allowed to wrap around
+pub trait Clock {
+    /// Returns the current time
+    fn now(&self) -> u32;
+}
+
+/// Wraps a `PinDebouncer` and feeds it with virtual ticks computed from a `Clock`, instead of
+/// one tick per call. This allows the same tick based configuration to be used in event driven
+/// firmware without a periodic timer, e.g. updating only on pin change interrupts
+///
+/// Since the pin is assumed to keep its level between calls, the elapsed ticks are fed with the
+/// level given in the previous call. The debouncer only advances when `update` is called, so a
+/// wake up must also be scheduled after the debouncing period of an edge to observe a settled
+/// pin. The elapsed ticks are replayed as by `PinDebouncer::update_at`, costing one call to
+/// `PinDebouncer::update` each up to the ones needed for the counters to saturate
+#[derive(Debug)]
+pub struct ClockedPinDebouncer<T: Ticks = u32> {
+    debouncer: PinDebouncer<T>,
+    tick_period: u32,
+    last_tick: u32,
+    level: bool,
+}
+
+impl<T: Ticks> ClockedPinDebouncer<T> {
+    /// Returns a ClockedPinDebouncer struct, starting with the pin released. Returns
+    /// `Error::ZeroTickPeriod` if `tick_period` is zero
+    ///
+    /// # Arguments
+    ///
+    /// * `debouncer` - The debouncer to be fed, with all its timings given in ticks
+    /// * `tick_period` - The duration of one tick in the unit of `clock`, must be non-zero
+    /// * `clock` - The time source, read once to start counting
+    pub fn new(
+        debouncer: PinDebouncer<T>,
+        tick_period: u32,
+        clock: &impl Clock,
+    ) -> Result<ClockedPinDebouncer<T>, Error> {
+        if tick_period == 0 {
+            return Err(Error::ZeroTickPeriod);
+        }
+        // The level read from a released pin, high for an active-low one
+        let level = debouncer.active_low;
+        Ok(ClockedPinDebouncer {
+            debouncer,
+            tick_period,
+            last_tick: clock.now(),
+            level,
+        })
+    }
+
+    /// Calls the `update` method of the inner debouncer once for each tick period elapsed since
+    /// the last tick, using the level of the previous call as the sample, then records `pressed`
+    /// as the current level. The fraction of a tick left is carried to the next call. Returns
+    /// true if any of the updates completed a window
+    pub fn update(&mut self, pressed: bool, clock: &impl Clock) -> bool {
+        let ticks = clock.now().wrapping_sub(self.last_tick) / self.tick_period;
+        let completed = self.debouncer.replay(self.level, u64::from(ticks));
+        self.last_tick = self
+            .last_tick
+            .wrapping_add(ticks.wrapping_mul(self.tick_period));
+        self.level = pressed;
+        completed
+    }
+
+    /// Returns the state of the inner debouncer, see `PinDebouncer::get_state`
+    pub fn get_state(&mut self) -> BtnState {
+        self.debouncer.get_state()
+    }
+
+    /// Returns a reference to the inner debouncer
+    pub fn debouncer(&self) -> &PinDebouncer<T> {
+        &self.debouncer
+    }
+
+    /// Returns a mutable reference to the inner debouncer
+    pub fn debouncer_mut(&mut self) -> &mut PinDebouncer<T> {
+        &mut self.debouncer
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_must_use)]
+mod tests {
+
+    use super::*;
+    use core::cell::Cell;
+
+    struct FakeClock(Cell<u32>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> u32 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn clocked_active_low() {
+        // The ticks before the first call are fed with the released level of the pin
+        let clock = FakeClock(Cell::new(0));
+        let mut clocked =
+            ClockedPinDebouncer::new(PinDebouncer::new_active_low(4, 20, 100), 1, &clock).unwrap();
+        clock.0.set(8);
+        assert!(clocked.update(true, &clock));
+        assert_eq!(BtnState::UnPressed, clocked.get_state());
+        clock.0.set(12);
+        assert!(clocked.update(false, &clock));
+        assert_eq!(BtnState::UnPressed, clocked.get_state());
+    }
+
+    #[test]
+    fn clocked_virtual_ticks() {
+        let clock = FakeClock(Cell::new(u32::MAX - 5));
+        let mut clocked =
+            ClockedPinDebouncer::new(PinDebouncer::new(4, 20, 100), 2, &clock).unwrap();
+
+        clock.0.set(u32::MAX);
+        assert!(!clocked.update(true, &clock));
+        assert_eq!(BtnState::UnPressed, clocked.get_state());
+
+        clock.0.set(3);
+        assert!(clocked.update(true, &clock));
+        assert_eq!(BtnState::UnPressed, clocked.get_state());
+        clock.0.set(11);
+        assert!(clocked.update(false, &clock));
+        assert_eq!(BtnState::ChangedToPressed, clocked.get_state());
+
+        // A long sleep only replays the ticks needed for the counters to saturate, with the
+        // same outcome as feeding all of them
+        let mut fed = PinDebouncer::new(4, 20, 100);
+        for &(pressed, ticks) in [(false, 2), (true, 6), (false, 1_000_000)].iter() {
+            for _ in 0..ticks {
+                fed.update(pressed);
+            }
+        }
+        clock.0.set(11 + 2 * 1_000_000);
+        assert!(clocked.update(false, &clock));
+        assert_eq!(BtnState::UnPressed, clocked.get_state());
+        assert_eq!(
+            fed.ticks_since_change(),
+            clocked.debouncer().ticks_since_change()
+        );
+        assert_eq!(
+            fed.last_press_duration(),
+            clocked.debouncer().last_press_duration()
+        );
+    }
+
+    #[test]
+    fn clocked_zero_period() {
+        let clock = FakeClock(Cell::new(0));
+        assert!(matches!(
+            ClockedPinDebouncer::new(PinDebouncer::new(4, 20, 100), 0, &clock),
+            Err(Error::ZeroTickPeriod)
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+//! Named configuration of the debouncers, an alternative to the positional `new` arguments
+
+use crate::{DebounceMode, PortWord, PriorityMode};
+
+/// Configuration of a `PortDebouncer`, passed to `PortDebouncer::from_config`. All the times
+/// are in ticks and follow the same rules as the arguments of `new` and the setters, the
+/// defaults being the same as the ones in place after `new`:
+///
+/// ```rust
+/// use debouncer::{PortConfig, PortDebouncer};
+/// use debouncer::typenum::consts::*;
+///
+/// let port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::from_config(PortConfig {
+///     hold_ticks: 200,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct PortConfig<W: PortWord = u32> {
+    /// See `PortDebouncer::new`, defaults to 20
+    pub repeat_ticks: usize,
+    /// See `PortDebouncer::new`, defaults to 100
+    pub hold_ticks: usize,
+    /// See `PortDebouncer::set_repeat_interval_ticks`, defaults to `None`, i.e. `repeat_ticks`
+    pub repeat_interval_ticks: Option<usize>,
+    /// See `PortDebouncer::set_min_press_ticks`, defaults to zero
+    pub min_press_ticks: usize,
+    /// See `PortDebouncer::set_max_hold_ticks`, defaults to `None`, i.e. the repeat threshold
+    pub max_hold_ticks: Option<usize>,
+    /// See `PortDebouncer::set_confirm_windows`, defaults to one
+    pub confirm_windows: usize,
+    /// See `PortDebouncer::set_normally_closed`, defaults to zero
+    pub normally_closed: W,
+    /// See `PortDebouncer::set_enabled_mask`, defaults to all the pins enabled
+    pub enabled: W,
+    /// See `PortDebouncer::set_priority_mode`, defaults to `PriorityMode::EdgeFirst`
+    pub priority: PriorityMode,
+    /// See `PortDebouncer::set_debounce_mode`, defaults to `DebounceMode::AllAgree`
+    pub mode: DebounceMode,
+}
+
+impl<W: PortWord> Default for PortConfig<W> {
+    fn default() -> Self {
+        PortConfig {
+            repeat_ticks: 20,
+            hold_ticks: 100,
+            repeat_interval_ticks: None,
+            min_press_ticks: 0,
+            max_hold_ticks: None,
+            confirm_windows: 1,
+            normally_closed: W::ZERO,
+            enabled: W::ONES,
+            priority: PriorityMode::EdgeFirst,
+            mode: DebounceMode::AllAgree,
+        }
+    }
+}
+
+/// Configuration of a `PinDebouncer`, passed to `PinDebouncer::from_config`. All the times are
+/// in ticks and follow the same rules as the arguments of `new` and the setters, the defaults
+/// being the same as the ones in place after `new`
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct PinConfig {
+    /// See `PinDebouncer::new`, defaults to 4
+    pub press_ticks: u32,
+    /// See `PinDebouncer::set_release_ticks`, defaults to `None`, i.e. `press_ticks`
+    pub release_ticks: Option<u32>,
+    /// See `PinDebouncer::new`, defaults to 20
+    pub repeat_ticks: u32,
+    /// See `PinDebouncer::new`, defaults to 100
+    pub hold_ticks: u32,
+    /// See `PinDebouncer::set_repeat_interval_ticks`, defaults to `None`, i.e. `repeat_ticks`
+    pub repeat_interval_ticks: Option<u32>,
+    /// See `PinDebouncer::set_min_press_ticks`, defaults to zero
+    pub min_press_ticks: u32,
+    /// See `PinDebouncer::set_max_hold_ticks`, defaults to `None`, i.e. the repeat threshold
+    pub max_hold_ticks: Option<u32>,
+    /// See `PinDebouncer::set_priority_mode`, defaults to `PriorityMode::EdgeFirst`
+    pub priority: PriorityMode,
+}
+
+impl Default for PinConfig {
+    fn default() -> Self {
+        PinConfig {
+            press_ticks: 4,
+            release_ticks: None,
+            repeat_ticks: 20,
+            hold_ticks: 100,
+            repeat_interval_ticks: None,
+            min_press_ticks: 0,
+            max_hold_ticks: None,
+            priority: PriorityMode::EdgeFirst,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Debounced signal exposed as an input pin
+
+use core::convert::Infallible;
+
+use crate::{PinDebouncer, Ticks};
+
+/// Wraps a `PinDebouncer` and exposes its debounced level as an input pin, for code written
+/// against an input pin interface. With the `embedded-hal` feature it implements the
+/// `embedded_hal::digital::InputPin` trait, forwarding to the inherent `is_high` and `is_low`.
+/// A pressed button reads as high, regardless of the polarity of the samples fed to `update`
+#[derive(Debug)]
+pub struct DebouncedPin<T: Ticks = u32> {
+    debouncer: PinDebouncer<T>,
+}
+
+impl<T: Ticks> DebouncedPin<T> {
+    /// Returns a DebouncedPin struct
+    ///
+    /// # Arguments
+    ///
+    /// * `debouncer` - The debouncer to be fed through `update`
+    pub const fn new(debouncer: PinDebouncer<T>) -> DebouncedPin<T> {
+        DebouncedPin { debouncer }
+    }
+
+    /// Feeds a new sample of the pin, see `PinDebouncer::update`
+    pub fn update(&mut self, pressed: bool) -> bool {
+        self.debouncer.update(pressed)
+    }
+
+    /// Returns whether the debounced pin is pressed, see `PinDebouncer::level`
+    pub fn is_high(&mut self) -> Result<bool, Infallible> {
+        Ok(self.debouncer.level())
+    }
+
+    /// Returns whether the debounced pin is released, see `PinDebouncer::level`
+    pub fn is_low(&mut self) -> Result<bool, Infallible> {
+        Ok(!self.debouncer.level())
+    }
+
+    /// Returns a reference to the inner debouncer
+    pub fn debouncer(&self) -> &PinDebouncer<T> {
+        &self.debouncer
+    }
+
+    /// Returns a mutable reference to the inner debouncer
+    pub fn debouncer_mut(&mut self) -> &mut PinDebouncer<T> {
+        &mut self.debouncer
+    }
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<T: Ticks> embedded_hal::digital::ErrorType for DebouncedPin<T> {
+    type Error = Infallible;
+}
+
+#[cfg(feature = "embedded-hal")]
+impl<T: Ticks> embedded_hal::digital::InputPin for DebouncedPin<T> {
+    fn is_high(&mut self) -> Result<bool, Infallible> {
+        DebouncedPin::is_high(self)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Infallible> {
+        DebouncedPin::is_low(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn debounced_pin_level() {
+        let mut pin = DebouncedPin::new(PinDebouncer::new(4, 20, 100));
+        assert_eq!(Ok(true), pin.is_low());
+
+        for _ in 0..4 {
+            pin.update(true);
+        }
+        assert_eq!(Ok(true), pin.is_high());
+        assert_eq!(Ok(false), pin.is_low());
+    }
+
+    #[cfg(feature = "embedded-hal")]
+    #[test]
+    fn debounced_pin_input_pin() {
+        use embedded_hal::digital::InputPin;
+
+        fn read(pin: &mut impl InputPin) -> bool {
+            pin.is_high().unwrap()
+        }
+
+        let mut pin = DebouncedPin::new(PinDebouncer::new(4, 20, 100));
+        assert!(!read(&mut pin));
+        for _ in 0..4 {
+            pin.update(true);
+        }
+        assert!(read(&mut pin));
+    }
+}
@@ -18,6 +18,14 @@
 //! in sequential order, passing a higher value to `get_state` causes it to return an error.
 //! **NOTE:** Buttons count starts at zero.
 //!
+//! The `recommend_samples` and `recommend_ticks` helpers compute `N` and the `new` arguments from
+//! durations in milliseconds.
+//!
+//! The port value is a `u32` by default, memory constrained targets with fewer buttons can use a
+//! smaller word through the `W` generic argument, see `PortWord`.
+//! Likewise, `PinDebouncer` counts its ticks in a `u32` by default, see `Ticks` for the other
+//! types.
+//!
 //! ## Example
 //! ```rust
 //! use debouncer::{PortDebouncer, BtnState};
@@ -68,75 +76,829 @@
 
 #![no_std]
 
-use generic_array::typenum::Unsigned;
+// generic-array 0.14.9 deprecated its whole 0.14 API in favour of 1.x, so the items using it
+// allow the deprecation instead of the whole crate
+#[allow(deprecated)]
+use generic_array::sequence::GenericSequence;
+use generic_array::typenum::{Unsigned, U0};
+#[allow(deprecated)]
 use generic_array::{ArrayLength, GenericArray};
 
 pub use generic_array::typenum;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+mod analog;
+mod array;
+mod clocked;
+mod config;
+mod hal;
+mod sequence;
+mod shift;
+mod source;
+mod ticks;
+mod toggle;
+mod tristate;
+mod value;
+mod word;
+pub use analog::AnalogDebouncer;
+pub use array::PinDebouncerArray;
+pub use clocked::{Clock, ClockedPinDebouncer};
+pub use config::{PinConfig, PortConfig};
+pub use hal::DebouncedPin;
+pub use sequence::{SequenceDebouncer, SequenceStep};
+pub use shift::ShiftPortDebouncer;
+pub use source::{PortChannel, StateSource};
+pub use ticks::Ticks;
+pub use toggle::ToggleDebouncer;
+pub use tristate::{SwitchPosition, TriStateDebouncer};
+pub use value::ValueDebouncer;
+pub use word::PortWord;
+
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
+#[cfg(feature = "std")]
+pub mod sim;
+#[cfg(feature = "std")]
+mod timed;
+#[cfg(feature = "std")]
+pub use timed::TimedDebouncer;
+
+#[cfg(feature = "async")]
+mod wait;
+
 #[derive(Debug)]
 pub enum Error {
     /// Error caused by querying the state of a pin which was not initialized during the creation of
     /// the `PortDebouncer` struct
     BtnUninitialized,
+    /// Error caused by creating a `PortDebouncer` with more buttons than bits in the port word
+    TooManyButtons,
+    /// Error caused by registering a sequence with more steps than a `SequenceDebouncer` can hold
+    SequenceTooLong,
+    /// Error caused by passing a per pin table whose length doesn't match the number of buttons
+    LengthMismatch,
+    /// Error caused by passing an output buffer too small for the requested data
+    BufferTooSmall,
+    /// Error caused by creating a clock or time driven debouncer with a zero tick period
+    ZeroTickPeriod,
+    /// Error caused by creating a debouncer with a window length it can't hold
+    InvalidWindow,
+}
+
+impl Error {
+    /// Returns a small integer identifying the error, stable across versions so it can be
+    /// reported over a bus or an FFI boundary. New variants get new codes, existing codes are
+    /// never reused
+    pub fn code(&self) -> u8 {
+        match self {
+            Error::BtnUninitialized => 1,
+            Error::TooManyButtons => 2,
+            Error::SequenceTooLong => 3,
+            Error::LengthMismatch => 4,
+            Error::BufferTooSmall => 5,
+            Error::ZeroTickPeriod => 6,
+            Error::InvalidWindow => 7,
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::BtnUninitialized => f.write_str("button not initialized"),
+            Error::TooManyButtons => f.write_str("more buttons than bits in the port word"),
+            Error::SequenceTooLong => f.write_str("sequence longer than the steps capacity"),
+            Error::LengthMismatch => f.write_str("table length doesn't match the buttons count"),
+            Error::BufferTooSmall => f.write_str("output buffer too small"),
+            Error::ZeroTickPeriod => f.write_str("tick period must be non-zero"),
+            Error::InvalidWindow => f.write_str("window length out of the supported range"),
+        }
+    }
+}
+
+/// Returns the number of samples `N` that gives a debouncing period of at least `debounce_ms`
+/// when `update` is called every `tick_period_ms`, and never less than one
+pub fn recommend_samples(debounce_ms: u32, tick_period_ms: u32) -> usize {
+    let tick_period_ms = tick_period_ms.max(1);
+    debounce_ms.div_ceil(tick_period_ms).max(1) as usize
+}
+
+/// Returns the `hold_ticks` or `repeat_ticks` argument for `PortDebouncer::new` matching a
+/// duration of at least `duration_ms` when `update` is called every `tick_period_ms`. The result
+/// is rounded up to a multiple of `samples`, i.e. `N`, and it's never less than `samples`
+pub fn recommend_ticks(duration_ms: u32, tick_period_ms: u32, samples: usize) -> usize {
+    let samples = samples.max(1);
+    let ticks = recommend_samples(duration_ms, tick_period_ms);
+    ticks.div_ceil(samples).saturating_mul(samples)
+}
+
+/// Maps `duration` from `[start, start + span]` onto `[0, 255]`, clamping outside that range
+fn scale_level(duration: u64, start: u64, span: u64) -> u8 {
+    if duration < start {
+        0
+    } else if span == 0 {
+        u8::MAX
+    } else {
+        let progress = u128::from((duration - start).min(span));
+        (progress * 255 / u128::from(span)) as u8
+    }
+}
+
+/// Converts a number of ticks to milliseconds, saturated at `u32::MAX` instead of wrapping
+fn ticks_to_ms<T: Ticks>(ticks: T, tick_period_ms: u32) -> u32 {
+    let ms = ticks.to_u64().saturating_mul(u64::from(tick_period_ms));
+    core::convert::TryFrom::try_from(ms).unwrap_or(u32::MAX)
 }
 
+/// Debounced state of a button. The discriminants are stable across versions so a state can be
+/// cast with `as u8` and sent over a bus or an FFI boundary, see `from_u8` for the reverse
+/// mapping. Existing values are frozen and new variants get explicit higher values
 #[derive(PartialEq, Copy, Clone, Debug)]
+#[repr(u8)]
 pub enum BtnState {
     Pressed = 0,
     UnPressed = 1,
     Repeat = 2,
     Hold = 3,
     ChangedToPressed = 4,
+    /// Pressed past the second threshold of an `AnalogDebouncer`, never reported by the other
+    /// debouncers
+    FirmPressed = 5,
+}
+
+impl BtnState {
+    /// Returns the state matching a discriminant, as obtained with `state as u8`, or `None` for
+    /// an unknown value
+    pub fn from_u8(value: u8) -> Option<BtnState> {
+        match value {
+            0 => Some(BtnState::Pressed),
+            1 => Some(BtnState::UnPressed),
+            2 => Some(BtnState::Repeat),
+            3 => Some(BtnState::Hold),
+            4 => Some(BtnState::ChangedToPressed),
+            5 => Some(BtnState::FirmPressed),
+            _ => None,
+        }
+    }
+}
+
+/// Precedence between the edge and the duration states, for when a button is just pressed and
+/// already past the hold threshold in the same window. This only happens with a hold threshold
+/// shorter than two windows
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum PriorityMode {
+    /// `ChangedToPressed` wins over `Hold` and `Repeat`, this is the default
+    EdgeFirst,
+    /// `Hold` and `Repeat` win over `ChangedToPressed`
+    DurationFirst,
+}
+
+/// How a button was released, as returned by the `release_kind` methods
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum ReleaseKind {
+    /// Released before reaching the hold state
+    Tap,
+    /// Released after reaching the hold state
+    LongHold,
+}
+
+/// How the samples of a window are combined into the debounced state of a pin, see
+/// `PortDebouncer::set_debounce_mode`
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum DebounceMode {
+    /// The pin is pressed only if all the `N` samples of the window agree, this is the default
+    AllAgree,
+    /// The pin is pressed if at least this many of the `N` samples of the window are pressed,
+    /// which better rejects symmetric noise. It should be between one and `N`
+    Majority(usize),
+}
+
+/// Copy of the debounced output of a `PortDebouncer`, as returned by its `snapshot` method
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[allow(deprecated)]
+pub struct PortSnapshot<BTNS: ArrayLength<T>, W: PortWord = u32, T: Ticks = u32> {
+    /// Debounced state of the port from the last completed window, one bit per pin
+    pub debounced_state: W,
+    /// Pins which changed to pressed in the last completed window, one bit per pin
+    pub changed_to_pressed: W,
+    /// Per pin number of ticks pressed, counted in steps of `N` at each window, used for the hold
+    /// and repeat states
+    pub counters: GenericArray<T, BTNS>,
+}
+
+/// Details of a completed window of a `PortDebouncer`, as given to the closure of its
+/// `update_detailed` method. All the fields have one bit per pin
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub struct UpdateInfo<W: PortWord = u32> {
+    /// Bitwise AND of the raw samples of the window, before any other filtering
+    pub raw_and: W,
+    /// Debounced state of the port from this window
+    pub debounced_state: W,
+    /// Debounced state of the port from the previous window
+    pub last_debounced_state: W,
+    /// Pins which changed to pressed in this window
+    pub changed_to_pressed: W,
+    /// Pins which were released in this window
+    pub changed_to_unpressed: W,
 }
 
-pub struct PortDebouncer<N: ArrayLength<u32> + Unsigned, BTNS: ArrayLength<u32> + Unsigned> {
-    port_states: GenericArray<u32, N>,
+/// Entry of the `PortDebouncer` state change log: the tick at which the change was detected, the
+/// pin and its new state
+pub type HistoryEntry = (u32, u8, BtnState);
+
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct PortDebouncer<
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned = U0,
+    W: PortWord = u32,
+    BNC: ArrayLength<u32> + Unsigned = U0,
+    T: Ticks = u32,
+    WIN: ArrayLength<u32> + Unsigned = U0,
+    THR: ArrayLength<T> + Unsigned = U0,
+    TRN: ArrayLength<BtnState> + Unsigned = U0,
+> {
+    port_states: GenericArray<W, N>,
     current_index: usize,
-    last_debounced_state: u32,
-    debounced_state: u32,
-    changed_to_pressed: u32,
+    tick: u32,
+    warmed_up: bool,
+    last_debounced_state: W,
+    debounced_state: W,
+    changed_to_pressed: W,
+    unread_presses: W,
+    sticky_press: bool,
+    changed: W,
+    sticky_changed: W,
+    normally_closed: W,
+    enabled: W,
+    priority: PriorityMode,
+    mode: DebounceMode,
+    repeating: W,
+    repeat_ended: W,
+    hold_edge: W,
+    lockout_mask: W,
+    lockout_winner: Option<usize>,
+    just_released: W,
+    release_pending: W,
+    long_released: W,
+    toggled: W,
     repeat_ticks: usize,
+    min_repeat_ticks: usize,
+    repeat_step: usize,
+    repeat_interval_ticks: Option<usize>,
+    repeat_enabled: bool,
     hold_ticks: usize,
-    counter: GenericArray<u32, BTNS>,
+    min_press_ticks: usize,
+    max_hold_ticks: Option<usize>,
+    confirm_windows: usize,
+    confirm_count: GenericArray<u32, BTNS>,
+    pin_windows: GenericArray<u32, WIN>,
+    release_samples: usize,
+    grace_ticks: usize,
+    grace_count: GenericArray<u32, BTNS>,
+    pin_hold_ticks: GenericArray<T, THR>,
+    pin_repeat_ticks: GenericArray<T, THR>,
+    counter: GenericArray<T, BTNS>,
+    repeat_rate: GenericArray<T, BTNS>,
+    repeat_index: GenericArray<u32, BTNS>,
+    presses: GenericArray<u32, BTNS>,
+    total_presses: u32,
+    hold_duration: GenericArray<T, BTNS>,
+    last_press_duration: GenericArray<T, BTNS>,
+    changed_at: GenericArray<u32, BTNS>,
+    window_states: GenericArray<BtnState, TRN>,
+    previous_states: GenericArray<BtnState, TRN>,
+    history: GenericArray<HistoryEntry, HIST>,
+    history_len: usize,
+    bounces: GenericArray<u32, BNC>,
+    #[cfg(feature = "async")]
+    pending_changes: W,
+    #[cfg(feature = "async")]
+    waker: Option<core::task::Waker>,
 }
 
-impl<N, BTNS> PortDebouncer<N, BTNS>
+#[allow(deprecated)]
+impl<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
 where
-    N: ArrayLength<u32> + Unsigned,
-    BTNS: ArrayLength<u32> + Unsigned,
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
 {
+    /// Number of buttons debounced, i.e. `BTNS`
+    pub const CAPACITY: usize = BTNS::USIZE;
+
     /// Returns a PortDebouncer struct
     ///
     /// # Generic arguments
     ///
     /// * `N` - Number of ticks before the pin is considered to be pressed, Unsigned type of the
-    /// typenum crate
+    ///   typenum crate
     /// * `BTNS` - Number of buttons which should be initialized for debouncing. The buttons are
-    /// considered to be the bits in sequence order (from least to most significance) in the input
-    /// from the `update` method
+    ///   considered to be the bits in sequence order (from least to most significance) in the input
+    ///   from the `update` method. Buttons past the width of `W` are never pressed and the per pin
+    ///   methods return `Error::BtnUninitialized` for them, `try_new` rejects such a configuration
+    /// * `HIST` - Number of state changes kept in the log returned by `history`, defaults to zero,
+    ///   i.e. no log and no extra memory
+    /// * `W` - Unsigned integer type of the port value, see `PortWord`. Defaults to `u32`, smaller
+    ///   types save memory on targets with fewer buttons
+    /// * `BNC` - Number of buttons, from the zeroth one, whose bounces are counted for
+    ///   `bounce_count`, defaults to zero, i.e. no counting and no extra memory
+    /// * `T` - Unsigned integer type of the per pin counters, see `Ticks`. Defaults to `u32`, a
+    ///   smaller type saves memory on targets with many buttons and a bigger one allows longer
+    ///   thresholds and measured holds
+    /// * `WIN` - Number of buttons, from the zeroth one, whose window can be shortened with
+    ///   `set_pin_window`, defaults to zero, i.e. every button uses all the `N` samples and no
+    ///   extra memory
+    /// * `THR` - Number of buttons, from the zeroth one, with hold and repeat thresholds of their
+    ///   own, see `set_all_timings`, defaults to zero, i.e. every button uses the thresholds of the
+    ///   port and no extra memory
+    /// * `TRN` - Number of buttons, from the zeroth one, whose previous state is kept for
+    ///   `transition`, defaults to zero, i.e. no tracking and no extra memory
     ///
     /// # Arguments
     ///
     /// * `repeat_ticks` - The number of ticks after que hold state at which the button is considered
-    /// to be in the repeat state, i.e. in the current implementation the button must be first past
-    /// the hold state before reaching the repeat state. This number must be a multiple of the
-    /// `press_ticks` (`N`) for better accuracy
+    ///   to be in the repeat state, i.e. in the current implementation the button must be first past
+    ///   the hold state before reaching the repeat state. Zero disables the repeat state, see
+    ///   `set_repeat_enabled`
     ///
     /// * `hold_ticks` - The number of ticks before the pin is considered to be in the hold state
-    /// This number must be a multiple of the `press_ticks` for better accuracy
-    pub fn new(repeat_ticks: usize, hold_ticks: usize) -> PortDebouncer<N, BTNS> {
+    ///
+    /// The states are only updated once per window, so each threshold is reached at the first
+    /// window completed at or after it. Repeats keep the remainder, so on average they're exactly
+    /// `repeat_ticks` apart even if it isn't a multiple of `N`
+    pub fn new(
+        repeat_ticks: usize,
+        hold_ticks: usize,
+    ) -> PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> {
+        let hold_ticks = hold_ticks.max(N::USIZE);
         PortDebouncer {
             port_states: GenericArray::default(),
             current_index: 0,
-            last_debounced_state: 0,
-            debounced_state: 0,
-            changed_to_pressed: 0,
-            repeat_ticks: repeat_ticks / N::USIZE,
-            hold_ticks: hold_ticks / N::USIZE - 1,
+            tick: 0,
+            warmed_up: false,
+            last_debounced_state: W::ZERO,
+            debounced_state: W::ZERO,
+            changed_to_pressed: W::ZERO,
+            unread_presses: W::ZERO,
+            sticky_press: false,
+            changed: W::ZERO,
+            sticky_changed: W::ZERO,
+            normally_closed: W::ZERO,
+            enabled: W::ONES,
+            priority: PriorityMode::EdgeFirst,
+            mode: DebounceMode::AllAgree,
+            repeating: W::ZERO,
+            repeat_ended: W::ZERO,
+            hold_edge: W::ZERO,
+            lockout_mask: W::ZERO,
+            lockout_winner: None,
+            just_released: W::ZERO,
+            release_pending: W::ZERO,
+            long_released: W::ZERO,
+            toggled: W::ZERO,
+            repeat_ticks,
+            min_repeat_ticks: repeat_ticks,
+            repeat_step: 0,
+            repeat_interval_ticks: None,
+            repeat_enabled: true,
+            hold_ticks,
+            min_press_ticks: 0,
+            max_hold_ticks: None,
+            confirm_windows: 1,
+            confirm_count: GenericArray::default(),
+            pin_windows: GenericArray::generate(|_| N::U32),
+            release_samples: 0,
+            grace_ticks: 0,
+            grace_count: GenericArray::default(),
+            pin_hold_ticks: GenericArray::generate(|_| T::from_usize(hold_ticks)),
+            pin_repeat_ticks: GenericArray::generate(|_| T::from_usize(repeat_ticks)),
             counter: GenericArray::default(),
+            repeat_rate: GenericArray::generate(|_| T::from_usize(repeat_ticks)),
+            repeat_index: GenericArray::default(),
+            presses: GenericArray::default(),
+            total_presses: 0,
+            hold_duration: GenericArray::default(),
+            last_press_duration: GenericArray::default(),
+            changed_at: GenericArray::default(),
+            window_states: GenericArray::generate(|_| BtnState::UnPressed),
+            previous_states: GenericArray::generate(|_| BtnState::UnPressed),
+            history: GenericArray::generate(|_| (0, 0, BtnState::UnPressed)),
+            history_len: 0,
+            bounces: GenericArray::default(),
+            #[cfg(feature = "async")]
+            pending_changes: W::ZERO,
+            #[cfg(feature = "async")]
+            waker: None,
+        }
+    }
+
+    /// Returns a PortDebouncer struct configured from the named fields of `config`, every field
+    /// being applied as by the matching setter, see `PortConfig`
+    pub fn from_config(
+        config: PortConfig<W>,
+    ) -> PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> {
+        let mut debouncer = Self::new(config.repeat_ticks, config.hold_ticks);
+        debouncer.set_min_press_ticks(config.min_press_ticks);
+        if let Some(max_hold_ticks) = config.max_hold_ticks {
+            debouncer.set_max_hold_ticks(max_hold_ticks);
+        }
+        if let Some(interval) = config.repeat_interval_ticks {
+            debouncer.set_repeat_interval_ticks(interval);
+        }
+        debouncer.set_confirm_windows(config.confirm_windows);
+        debouncer.set_normally_closed(config.normally_closed);
+        debouncer.set_enabled_mask(config.enabled);
+        debouncer.set_priority_mode(config.priority);
+        debouncer.set_debounce_mode(config.mode);
+        debouncer
+    }
+
+    /// Same as `new`, but checks that all the `BTNS` buttons fit in the `W` port value passed to
+    /// `update`, returning `Error::TooManyButtons` otherwise, and that the window holds at least
+    /// one sample, returning `Error::InvalidWindow` for an `N` of zero
+    pub fn try_new(repeat_ticks: usize, hold_ticks: usize) -> Result<Self, Error> {
+        if N::USIZE == 0 {
+            return Err(Error::InvalidWindow);
+        }
+        if BTNS::USIZE > W::BITS {
+            return Err(Error::TooManyButtons);
+        }
+        Ok(PortDebouncer::new(repeat_ticks, hold_ticks))
+    }
+
+    /// Changes the hold threshold in place, keeping the debouncing history. A button already being
+    /// held won't jump straight to `Repeat` if the threshold shrinks
+    ///
+    /// # Arguments
+    ///
+    /// * `hold_ticks` - Same as in `new`
+    pub fn set_hold_ticks(&mut self, hold_ticks: usize) {
+        self.hold_ticks = hold_ticks.max(N::USIZE);
+        for hold in self.pin_hold_ticks.iter_mut() {
+            *hold = T::from_usize(self.hold_ticks);
+        }
+        self.clamp_counters();
+    }
+
+    /// Changes the repeat threshold in place, keeping the debouncing history. A button already
+    /// being held won't jump straight to `Repeat` if the threshold shrinks
+    ///
+    /// # Arguments
+    ///
+    /// * `repeat_ticks` - Same as in `new`
+    pub fn set_repeat_ticks(&mut self, repeat_ticks: usize) {
+        self.repeat_ticks = repeat_ticks;
+        self.min_repeat_ticks = self.min_repeat_ticks.min(self.repeat_ticks);
+        for repeat in self.pin_repeat_ticks.iter_mut() {
+            *repeat = T::from_usize(repeat_ticks);
+        }
+        self.reset_repeat_rates();
+        self.clamp_counters();
+    }
+
+    /// Returns the hold threshold of the port in windows, i.e. how many windows a button must be
+    /// pressed, counting the one where it was pressed, before it's reported as `Hold`. As the
+    /// states are only updated once per window, this is `hold_ticks` divided by `N` and rounded
+    /// up. Per-button thresholds set by `set_all_timings` aren't taken into account
+    pub fn effective_hold_ticks(&self) -> usize {
+        self.hold_ticks.div_ceil(N::USIZE)
+    }
+
+    /// Returns the repeat threshold of the port in windows, rounded up. Repeats keep the
+    /// remainder, so they alternate between this and one window less when `repeat_ticks` isn't a
+    /// multiple of `N`, see `new`
+    pub fn effective_repeat_ticks(&self) -> usize {
+        self.repeat_ticks.div_ceil(N::USIZE)
+    }
+
+    /// Returns the number of ticks after which a button held from the start of a window is
+    /// reported as `Hold`, i.e. the given `hold_ticks` rounded up to a multiple of `N`
+    pub fn hold_ticks_raw(&self) -> usize {
+        self.effective_hold_ticks().saturating_mul(N::USIZE)
+    }
+
+    /// Sets the hold and repeat thresholds of the first `THR` buttons from a table, e.g. a static
+    /// array, so buttons can have different timings. Each entry follows the same rules as the
+    /// arguments of `new`, and the setters of the whole port, e.g. `set_hold_ticks`, override the
+    /// table. The buttons past `THR` keep the thresholds of the port. Returns
+    /// `Error::LengthMismatch` if the table lengths don't match `THR`, leaving the thresholds
+    /// untouched
+    ///
+    /// # Arguments
+    ///
+    /// * `holds` - The `hold_ticks` of each button, from the zeroth pin up
+    /// * `repeats` - The `repeat_ticks` of each button, from the zeroth pin up
+    pub fn set_all_timings(&mut self, holds: &[T], repeats: &[T]) -> Result<(), Error> {
+        if holds.len() != THR::USIZE || repeats.len() != THR::USIZE {
+            return Err(Error::LengthMismatch);
+        }
+        for (pin_hold, &hold) in self.pin_hold_ticks.iter_mut().zip(holds) {
+            *pin_hold = hold.max(Self::window_ticks());
+        }
+        self.pin_repeat_ticks.copy_from_slice(repeats);
+        self.reset_repeat_rates();
+        self.clamp_counters();
+        Ok(())
+    }
+
+    /// Returns the hold threshold of the queried pin, its own one for the first `THR` pins
+    fn pin_hold(&self, pin: usize) -> T {
+        self.pin_hold_ticks
+            .get(pin)
+            .copied()
+            .unwrap_or_else(|| T::from_usize(self.hold_ticks))
+    }
+
+    /// Returns the repeat threshold of the queried pin, its own one for the first `THR` pins
+    fn pin_repeat(&self, pin: usize) -> T {
+        self.pin_repeat_ticks
+            .get(pin)
+            .copied()
+            .unwrap_or_else(|| T::from_usize(self.repeat_ticks))
+    }
+
+    fn reset_repeat_rates(&mut self) {
+        for index in 0..BTNS::USIZE {
+            self.repeat_rate[index] = self.pin_repeat(index);
+        }
+    }
+
+    fn clamp_counters(&mut self) {
+        for index in 0..BTNS::USIZE {
+            let max = self
+                .pin_hold(index)
+                .saturating_add(self.repeat_rate[index])
+                .saturating_sub(Self::window_ticks());
+            if self.counter[index] > max {
+                self.counter[index] = max;
+            }
+        }
+    }
+
+    /// Makes the repeat rate speed up the longer a button is held. The first `Repeat` of a hold
+    /// happens `initial_ticks` after the hold state, and each following `Repeat` comes `step`
+    /// ticks sooner than the previous one, until the interval reaches `min_ticks`. The rate goes
+    /// back to `initial_ticks` when the button is released
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_ticks` - Replaces the `repeat_ticks` given in `new`
+    /// * `min_ticks` - The fastest repeat interval, in ticks
+    /// * `step` - How many ticks the interval is shortened by after each `Repeat`
+    pub fn set_repeat_acceleration(&mut self, initial_ticks: usize, min_ticks: usize, step: usize) {
+        self.repeat_interval_ticks = None;
+        self.repeat_ticks = initial_ticks;
+        self.min_repeat_ticks = min_ticks;
+        self.repeat_step = step;
+        for repeat in self.pin_repeat_ticks.iter_mut() {
+            *repeat = T::from_usize(initial_ticks);
+        }
+        self.reset_repeat_rates();
+        self.clamp_counters();
+    }
+
+    /// Enables or disables the repeat state. While disabled, the counter of a held button stops
+    /// at the hold threshold and `get_state` keeps reporting `Hold` for as long as the button is
+    /// held, never `Repeat`, for buttons which should only report press and hold. Enabled by
+    /// default, a repeat interval of zero ticks disables it as well
+    pub fn set_repeat_enabled(&mut self, enabled: bool) {
+        self.repeat_enabled = enabled;
+    }
+
+    /// Sets the interval between repeats, following the keyboard "delay + rate" model: the first
+    /// `Repeat` of a hold comes `repeat_ticks` after the hold state, as given to `new`, and the
+    /// following ones every `repeat_interval_ticks`. By default the interval is the same as the
+    /// first delay. This replaces the acceleration set by `set_repeat_acceleration`
+    ///
+    /// # Arguments
+    ///
+    /// * `repeat_interval_ticks` - The number of ticks between two consecutive repeats
+    pub fn set_repeat_interval_ticks(&mut self, repeat_interval_ticks: usize) {
+        self.repeat_interval_ticks = Some(repeat_interval_ticks);
+    }
+
+    /// Sets the minimum number of ticks a button must be held before it's reported as pressed.
+    /// Shorter contacts, even if they pass the debouncing, are reported as `UnPressed` and never
+    /// generate a `ChangedToPressed`. This is meant to filter accidental touches and defaults to
+    /// zero, i.e. no filtering
+    ///
+    /// # Arguments
+    ///
+    /// * `min_press_ticks` - The number of ticks before the pin is considered to be pressed. As with
+    ///   `hold_ticks`, it's reached at the first window completed at or after it, and it should
+    ///   be smaller than `hold_ticks`
+    pub fn set_min_press_ticks(&mut self, min_press_ticks: usize) {
+        self.min_press_ticks = min_press_ticks;
+    }
+
+    /// Sets how long a hold can be measured by `held_ticks`, which by default stops counting at
+    /// the repeat threshold (`hold_ticks + repeat_ticks`). This doesn't change when the hold and
+    /// repeat states are reported, it only allows measuring longer holds, e.g. for a "hold 10
+    /// seconds to reset" feature
+    ///
+    /// # Arguments
+    ///
+    /// * `max_hold_ticks` - The longest hold measured, rounded up to a multiple of `N`
+    pub fn set_max_hold_ticks(&mut self, max_hold_ticks: usize) {
+        self.max_hold_ticks = Some(max_hold_ticks);
+    }
+
+    /// Marks the pins assumed to be already pressed at startup, e.g. a boot select button held
+    /// during power on. If they're still pressed at the first window, they're reported as
+    /// `Pressed` without a `ChangedToPressed`, and their hold time counts from that window. A
+    /// pin which turns out to be released is reported as released in the first window. Meant to
+    /// be called before the first window is completed
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Bit mask where the bits set represent the pins assumed to be pressed
+    pub fn assume_initial(&mut self, mask: W) {
+        let mask = mask & Self::btns_mask();
+        self.last_debounced_state |= mask;
+        // One window short of a press, so the first window completes it without an edge
+        let counter = self.pressed_ticks().saturating_sub(Self::window_ticks());
+        for index in Self::pins_in(mask) {
+            self.counter[index] = self.counter[index].max(counter);
+        }
+    }
+
+    /// Sets how the samples of a window are combined into the debounced state, see
+    /// `DebounceMode`. Defaults to `DebounceMode::AllAgree`
+    pub fn set_debounce_mode(&mut self, mode: DebounceMode) {
+        self.mode = mode;
+    }
+
+    /// Sets the number of samples, out of the `N` samples of each window, used to debounce the
+    /// queried pin. Only the most recent `n` samples are combined at the end of each window, so
+    /// a clean button may settle sooner than a noisy one, while the states are still updated once
+    /// every `N` ticks. With `DebounceMode::Majority`, the votes are counted over these samples.
+    /// Only the first `WIN` pins have a window of their own, `Error::BtnUninitialized` is returned
+    /// for the others
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which window must be set. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    /// * `n` - The number of samples, clamped between one and `N`
+    pub fn set_pin_window(&mut self, pin: usize, n: usize) -> Result<(), Error> {
+        Self::check_pin(pin)?;
+        let window = self
+            .pin_windows
+            .get_mut(pin)
+            .ok_or(Error::BtnUninitialized)?;
+        *window = n.clamp(1, N::USIZE) as u32;
+        Ok(())
+    }
+
+    /// Sets how many samples must be released before a pressed button is released. By default a
+    /// single released sample in a window releases the button, so releases are detected sooner
+    /// than presses, which need all the samples of the window. With `n` samples, a pressed button
+    /// is only released at the end of a window whose last `n` samples are all released:
+    ///
+    /// * A press is reported at the end of the first window with all its `N` samples pressed
+    /// * A release is reported at the end of the first window ending with `n` released samples,
+    ///   i.e. `n` to `N + n - 1` ticks after the button is released for good
+    ///
+    /// so `n = N` makes both edges symmetric. This applies on top of `set_debounce_mode` and
+    /// `set_pin_window`, which only decide when a button is pressed
+    ///
+    /// # Arguments
+    ///
+    /// * `n` - The number of released samples, clamped to `N`. Zero restores the default
+    pub fn set_release_samples(&mut self, n: usize) {
+        self.release_samples = n.min(N::USIZE);
+    }
+
+    /// Sets a grace period after a release during which a re-press continues the hold instead of
+    /// being a new press, e.g. for a finger briefly losing contact. A released button is kept
+    /// `Pressed` for up to `ticks` ticks, rounded up to whole windows, with its hold counter frozen,
+    /// so if it's pressed again meanwhile it resumes the hold where it was, without a new
+    /// `ChangedToPressed`. Otherwise it's released once the grace period is over. Defaults to zero
+    ///
+    /// # Arguments
+    ///
+    /// * `ticks` - The grace period in ticks, zero disables it
+    pub fn set_grace_ticks(&mut self, ticks: usize) {
+        self.grace_ticks = ticks;
+        self.grace_count = GenericArray::default();
+    }
+
+    /// Sets the number of consecutive windows that must agree on a new state before it's
+    /// committed, as a second filtering stage for switches that bounce for longer than a window.
+    /// Each new state is delayed by `m - 1` windows. The default, and the minimum, is one, i.e.
+    /// each window is committed right away
+    ///
+    /// # Arguments
+    ///
+    /// * `m` - The number of consecutive windows, values below one are taken as one
+    pub fn set_confirm_windows(&mut self, m: usize) {
+        self.confirm_windows = m.max(1);
+        self.confirm_count = GenericArray::default();
+    }
+
+    /// Marks the pins connected to normally-closed switches. The samples of these pins are inverted
+    /// before debouncing, so a `ChangedToPressed` is generated when the contact opens and the
+    /// pin is considered pressed while the contact stays open. Defaults to zero, i.e. all switches
+    /// are normally-open
+    ///
+    /// This is independent of the port polarity, which is still handled by negating the
+    /// `port_value` before passing it to `update`. The resulting pressed level is:
+    ///
+    /// | Port polarity | Value given to `update` | `normally_closed` bit | Pressed when pin is |
+    /// |---------------|-------------------------|-----------------------|---------------------|
+    /// | Active-high   | `port_value`            | 0                     | High                |
+    /// | Active-high   | `port_value`            | 1                     | Low                 |
+    /// | Active-low    | `!port_value`           | 0                     | Low                 |
+    /// | Active-low    | `!port_value`           | 1                     | High                |
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Bit mask where the bits set represent the normally-closed pins
+    pub fn set_normally_closed(&mut self, mask: W) {
+        self.normally_closed = mask;
+    }
+
+    /// Selects which pins are reported by `get_state`. Disabled pins are always reported as
+    /// `UnPressed`, and as released by the queries such as `pressed_count` or `first_pressed`,
+    /// but they are still debounced and their counters keep running, so enabling
+    /// them again resumes from their actual state. All pins are enabled by default
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Bit mask where the bits set represent the enabled pins
+    pub fn set_enabled_mask(&mut self, mask: W) {
+        self.enabled = mask;
+    }
+
+    /// Groups pins under a "first press wins" lockout, e.g. for quiz buzzers. The first pin of the
+    /// group to be pressed, the lowest one if several are pressed in the same window, becomes the
+    /// winner, see `lockout_winner`, and the other pins of the group are reported as `UnPressed`,
+    /// without any press event, until `clear_lockout` is called. Like disabled pins, they're still
+    /// debounced meanwhile, so one still held when the lockout is cleared is reported as `Pressed`
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Bit mask where the bits set represent the pins of the group
+    pub fn lockout_group(&mut self, mask: W) {
+        self.lockout_mask = mask;
+    }
+
+    /// Returns the pin which won the lockout group, if any, see `lockout_group`
+    pub fn lockout_winner(&self) -> Option<usize> {
+        self.lockout_winner
+    }
+
+    /// Releases the lockout, so the next press in the group picks a new winner
+    pub fn clear_lockout(&mut self) {
+        self.lockout_winner = None;
+    }
+
+    fn locked_out(&self) -> W {
+        match self.lockout_winner {
+            Some(winner) => self.lockout_mask & !W::bit(winner),
+            None => W::ZERO,
         }
     }
 
+    /// Sets which state `get_state` reports when a button is both just pressed and already in the
+    /// hold or repeat state, see `PriorityMode`
+    pub fn set_priority_mode(&mut self, priority: PriorityMode) {
+        self.priority = priority;
+    }
+
+    /// Enables or disables the sticky press mode, for loops which can't read the states every
+    /// window. While enabled, a `ChangedToPressed` isn't consumed by `get_state` nor dropped at
+    /// the next window, it stays latched, even if the button is released meanwhile, until it's
+    /// acknowledged with `ack_pressed`, so no press is ever missed. Disabling it drops the latched
+    /// presses at the next window. Disabled by default
+    pub fn set_sticky_press(&mut self, enabled: bool) {
+        self.sticky_press = enabled;
+    }
+
+    /// Acknowledges the latched `ChangedToPressed` of the queried pin, see `set_sticky_press`, so
+    /// `get_state` reports its current state again. Does nothing if no press is latched
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which press must be acknowledged. Where the zeroth pin is considered to be
+    ///   the least significant bit in the `port_value` used in the `update` method
+    pub fn ack_pressed(&mut self, pin: usize) -> Result<(), Error> {
+        Self::check_pin(pin)?;
+        self.unread_presses &= !W::bit(pin);
+        Ok(())
+    }
+
     /// This method should be called frequently according to the precision required by the
     /// application. The last N states will be used to debounce the pin, where N is the number
     /// chosen for the `press_ticks`. For example, if the user wants a 40ms deboucing time, one can
@@ -146,273 +908,4446 @@ where
     /// # Arguments
     ///
     /// * `port_value` - The port state in a given time, where its bits represent a pin state. The
-    /// pins are considered to be active-high. For an active-low port, the user can use the bitwise
-    /// negator operator `!` before passing the value to the method.
-    pub fn update(&mut self, port_value: u32) -> bool {
+    ///   pins are considered to be active-high. For an active-low port, the user can use the bitwise
+    ///   negator operator `!` before passing the value to the method.
+    ///
+    /// Returns true when this call completed a window of `N` samples, i.e. the debounced state
+    /// was just updated and should be queried. It doesn't mean that any button changed its state
+    #[must_use]
+    pub fn update(&mut self, port_value: W) -> bool {
+        if BNC::USIZE != 0 && self.current_index != 0 {
+            let edges = port_value ^ self.port_states[self.current_index - 1];
+            for (index, bounces) in self.bounces.iter_mut().enumerate() {
+                if edges & W::bit(index) != W::ZERO {
+                    *bounces = bounces.saturating_add(1);
+                }
+            }
+        }
         self.port_states[self.current_index] = port_value;
+        self.tick = self.tick.wrapping_add(1);
         if self.current_index != N::USIZE - 1 {
             self.current_index += 1;
             false
         } else {
             self.current_index = 0;
-            self.debounced_state = 0xFFFF_FFFF;
-            for &state in self.port_states.iter() {
-                self.debounced_state &= state;
+            self.warmed_up = true;
+            self.debounced_state = match self.mode {
+                DebounceMode::AllAgree => {
+                    let mut state = self.port_states.iter().fold(W::ONES, |state, &sample| {
+                        state & (sample ^ self.normally_closed)
+                    });
+                    for (index, &window) in self.pin_windows.iter().enumerate() {
+                        if window != N::U32 {
+                            let mask = W::bit(index);
+                            let recent = self.port_states[N::USIZE - window as usize..]
+                                .iter()
+                                .fold(mask, |state, &sample| {
+                                    state & (sample ^ self.normally_closed)
+                                });
+                            state = (state & !mask) | recent;
+                        }
+                    }
+                    state
+                }
+                DebounceMode::Majority(threshold) => {
+                    let mut state = W::ZERO;
+                    for index in 0..BTNS::USIZE {
+                        let window = self
+                            .pin_windows
+                            .get(index)
+                            .map_or(N::USIZE, |&n| n as usize);
+                        let start = N::USIZE - window;
+                        let votes = self.port_states[start..]
+                            .iter()
+                            .filter(|&&sample| {
+                                (sample ^ self.normally_closed) & W::bit(index) != W::ZERO
+                            })
+                            .count();
+                        if votes >= threshold {
+                            state |= W::bit(index);
+                        }
+                    }
+                    state
+                }
+            };
+            if self.release_samples != 0 {
+                let recent = self.port_states[N::USIZE - self.release_samples..]
+                    .iter()
+                    .fold(W::ZERO, |state, &sample| {
+                        state | (sample ^ self.normally_closed)
+                    });
+                self.debounced_state |= self.last_debounced_state & recent;
             }
-            self.changed_to_pressed = !self.last_debounced_state & self.debounced_state;
+            if self.confirm_windows > 1 {
+                let candidate = self.debounced_state;
+                self.debounced_state = self.last_debounced_state;
+                for (index, count) in self.confirm_count.iter_mut().enumerate() {
+                    let mask = W::bit(index);
+                    if (candidate ^ self.last_debounced_state) & mask == W::ZERO {
+                        *count = 0;
+                    } else {
+                        *count += 1;
+                        if *count as usize >= self.confirm_windows {
+                            *count = 0;
+                            self.debounced_state = self.debounced_state ^ mask;
+                        }
+                    }
+                }
+            }
+            let mut grace = W::ZERO;
+            if self.grace_ticks != 0 {
+                let grace_windows = self.grace_ticks.div_ceil(N::USIZE) as u32;
+                let dropped = self.last_debounced_state & !self.debounced_state;
+                for index in Self::pins_in(self.last_debounced_state) {
+                    if dropped & W::bit(index) == W::ZERO {
+                        self.grace_count[index] = 0;
+                    } else if self.grace_count[index] < grace_windows {
+                        self.grace_count[index] += 1;
+                        grace |= W::bit(index);
+                    } else {
+                        self.grace_count[index] = 0;
+                    }
+                }
+                self.debounced_state |= grace;
+            }
+            self.changed_to_pressed = W::ZERO;
+
+            let mut released = W::ZERO;
+            let mut long_released = W::ZERO;
+            let mut held = W::ZERO;
+            let min_press_ticks = T::from_usize(self.min_press_ticks);
+            let window_ticks = Self::window_ticks();
+            for index in 0..BTNS::USIZE {
+                let hold_ticks = self.pin_hold(index);
+                let max_hold = self
+                    .max_hold_ticks
+                    .map(T::from_usize)
+                    .unwrap_or(hold_ticks.saturating_add(self.pin_repeat(index)));
+                let duration = &mut self.hold_duration[index];
+                if self.debounced_state & W::bit(index) == W::ZERO {
+                    if *duration != T::ZERO {
+                        self.last_press_duration[index] = *duration;
+                    }
+                    *duration = T::ZERO;
+                } else if *duration < max_hold && grace & W::bit(index) == W::ZERO {
+                    *duration = duration.saturating_add(window_ticks);
+                }
 
-            for (index, btn_counter) in self.counter.iter_mut().enumerate() {
-                if (self.last_debounced_state & self.debounced_state & (1 << index)) != 0 {
-                    if *btn_counter < (self.hold_ticks + self.repeat_ticks) as u32 {
-                        *btn_counter += 1;
+                let mut btn_counter = self.counter[index];
+                let was_pressed = (self.last_debounced_state & W::bit(index)) != W::ZERO
+                    && btn_counter >= min_press_ticks;
+                let was_held = btn_counter >= hold_ticks;
+                if (self.last_debounced_state & self.debounced_state & W::bit(index)) != W::ZERO {
+                    let repeat_ticks = if self.repeat_enabled {
+                        self.repeat_rate[index]
+                    } else {
+                        T::ZERO
+                    };
+                    if btn_counter < hold_ticks.saturating_add(repeat_ticks)
+                        && grace & W::bit(index) == W::ZERO
+                    {
+                        if btn_counter < hold_ticks
+                            && btn_counter.saturating_add(window_ticks) >= hold_ticks
+                        {
+                            held |= W::bit(index);
+                        }
+                        btn_counter = btn_counter.saturating_add(window_ticks);
                     }
                 } else {
-                    *btn_counter = 0;
+                    btn_counter = if self.debounced_state & W::bit(index) != W::ZERO {
+                        window_ticks
+                    } else {
+                        T::ZERO
+                    };
+                    self.repeat_rate[index] = self.pin_repeat(index);
+                    self.repeat_index[index] = 0;
+                }
+                if !was_pressed
+                    && (self.debounced_state & W::bit(index)) != W::ZERO
+                    && btn_counter >= min_press_ticks
+                {
+                    self.changed_to_pressed |= W::bit(index);
+                } else if was_pressed && (self.debounced_state & W::bit(index)) == W::ZERO {
+                    released |= W::bit(index);
+                    if was_held {
+                        long_released |= W::bit(index);
+                    }
+                }
+                self.counter[index] = btn_counter;
+            }
+            if self.lockout_winner.is_none() {
+                let presses = self.changed_to_pressed & self.lockout_mask & self.enabled;
+                self.lockout_winner =
+                    (0..BTNS::USIZE).find(|&pin| presses & W::bit(pin) != W::ZERO);
+            }
+            let locked_out = self.locked_out();
+            self.changed_to_pressed &= !locked_out;
+            released &= !locked_out;
+            self.changed = self.changed_to_pressed | released;
+            if self.sticky_press {
+                self.unread_presses |= self.changed_to_pressed;
+            } else {
+                self.unread_presses = self.changed_to_pressed;
+            }
+            self.just_released = released & self.enabled;
+            self.release_pending = self.just_released;
+            self.long_released = long_released;
+            #[cfg(feature = "async")]
+            {
+                self.pending_changes |= self.changed;
+                if self.pending_changes & self.enabled != W::ZERO {
+                    if let Some(waker) = self.waker.take() {
+                        waker.wake();
+                    }
                 }
             }
+            self.toggled = self.toggled ^ (self.changed_to_pressed & self.enabled);
+            let presses = self.changed_to_pressed & self.enabled;
+            self.total_presses = self.total_presses.saturating_add(presses.count_ones());
+            for index in Self::pins_in(presses) {
+                self.presses[index] = self.presses[index].saturating_add(1);
+            }
+            self.hold_edge = (self.hold_edge | held) & self.debounced_state;
+            self.repeat_ended |= self.repeating & !self.debounced_state;
+            self.repeating &= self.debounced_state;
+
+            let changed = self.last_debounced_state ^ self.debounced_state;
+            self.sticky_changed |= self.changed & self.enabled;
+            for index in Self::pins_in(changed) {
+                self.changed_at[index] = self.tick;
+            }
             self.last_debounced_state = self.debounced_state;
+            for pin in 0..TRN::USIZE {
+                let state = self.observe_state(pin).unwrap_or(BtnState::UnPressed);
+                self.previous_states[pin] = core::mem::replace(&mut self.window_states[pin], state);
+            }
+
+            if HIST::USIZE != 0 {
+                for index in 0..BTNS::USIZE {
+                    if self.changed_to_pressed & W::bit(index) != W::ZERO {
+                        self.push_history(index, BtnState::ChangedToPressed);
+                    } else if released & W::bit(index) != W::ZERO {
+                        self.push_history(index, BtnState::UnPressed);
+                    } else if held & W::bit(index) != W::ZERO {
+                        self.push_history(index, BtnState::Hold);
+                    }
+                }
+            }
             true
         }
     }
 
+    fn push_history(&mut self, pin: usize, state: BtnState) {
+        if self.history_len == HIST::USIZE {
+            self.history.copy_within(1.., 0);
+        } else {
+            self.history_len += 1;
+        }
+        self.history[self.history_len - 1] = (self.tick, pin as u8, state);
+    }
+
+    /// Returns the last `HIST` state changes, from the oldest to the newest. A change is logged
+    /// when a button is pressed, released or reaches the hold state, along with the tick, i.e.
+    /// the number of calls to `update`, at which it was detected. The tick wraps around on
+    /// overflow
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history[..self.history_len]
+    }
+
     /// Returns the state of the queried pin. It is recommend to call this method each time after
     /// calling the `update` method N times, where N is the chosen `press_ticks`. This is done for
     /// avoiding losing any state change in the port
     ///
+    /// The states are checked in the order `ChangedToPressed`, `Repeat`, `Hold`, `Pressed`, the
+    /// first one to apply is returned. With `PriorityMode::DurationFirst`, `ChangedToPressed` is
+    /// checked after `Hold` instead
+    ///
+    /// A `ChangedToPressed` or a `Repeat` is consumed by this call, a second call in the same
+    /// window returns `Pressed` or `Hold` instead, so each edge is only reported once no matter
+    /// how many code paths read the state. `observe_state` can be used for any additional read,
+    /// it reports the same state without consuming it. A `ChangedToPressed` never read is dropped
+    /// at the next window, unless it's latched by `set_sticky_press`
+    ///
     /// # Arguments
     ///
     /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
-    /// significant bit in the `port_value` used in the `update` method
+    ///   significant bit in the `port_value` used in the `update` method
     pub fn get_state(&mut self, pin: usize) -> Result<BtnState, Error> {
-        if pin >= BTNS::USIZE {
-            return Err(Error::BtnUninitialized);
+        let state = self.observe_state(pin)?;
+        if state == BtnState::ChangedToPressed {
+            if !self.sticky_press {
+                self.unread_presses &= !W::bit(pin);
+            }
+        } else if state == BtnState::Repeat {
+            self.counter[pin] = self.counter[pin].saturating_sub(self.repeat_rate[pin]);
+            self.repeat_rate[pin] = match self.repeat_interval_ticks {
+                Some(interval) => T::from_usize(interval),
+                None => self.repeat_rate[pin]
+                    .saturating_sub(T::from_usize(self.repeat_step))
+                    .max(T::from_usize(self.min_repeat_ticks)),
+            };
+            self.repeating |= W::bit(pin);
+            self.repeat_index[pin] = self.repeat_index[pin].saturating_add(1);
+        }
+        Ok(state)
+    }
+
+    /// Same as `get_state`, but without consuming a pending `ChangedToPressed` or `Repeat`, so it
+    /// can be called any number of times, e.g. to render a UI twice per window
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn observe_state(&self, pin: usize) -> Result<BtnState, Error> {
+        Self::check_pin(pin)?;
+        if (self.enabled & !self.locked_out()) & W::bit(pin) == W::ZERO {
+            return Ok(BtnState::UnPressed);
         }
-        if self.changed_to_pressed & (1 << pin) != 0 {
+        let changed_to_pressed = self.unread_presses & W::bit(pin) != W::ZERO;
+        if changed_to_pressed && self.priority == PriorityMode::EdgeFirst {
             return Ok(BtnState::ChangedToPressed);
         }
-        if self.counter[pin] >= (self.hold_ticks + self.repeat_ticks) as u32 {
-            self.counter[pin] -= self.repeat_ticks as u32;
+        let hold_ticks = self.pin_hold(pin);
+        let counter = self.counter[pin];
+        let repeat_ticks = self.repeat_rate[pin];
+        if self.repeat_enabled
+            && repeat_ticks != T::ZERO
+            && counter >= hold_ticks.saturating_add(repeat_ticks)
+        {
             Ok(BtnState::Repeat)
-        } else if self.counter[pin] >= self.hold_ticks as u32 {
+        } else if counter >= hold_ticks {
             Ok(BtnState::Hold)
-        } else if self.debounced_state & (1 << pin) != 0 {
+        } else if changed_to_pressed {
+            Ok(BtnState::ChangedToPressed)
+        } else if self.debounced_state & W::bit(pin) != W::ZERO
+            && counter >= T::from_usize(self.min_press_ticks)
+        {
             Ok(BtnState::Pressed)
         } else {
             Ok(BtnState::UnPressed)
         }
     }
-}
-
-pub struct PinDebouncer {
-    current_index: u32,
-    last_debounced_state: BtnState,
-    debounced_state: BtnState,
-    press_ticks: u32,
-    repeat_ticks: u32,
-    hold_ticks: u32,
-    counter: u32,
-}
 
-impl PinDebouncer {
-    pub const fn new(press_ticks: u32, repeat_ticks: u32, hold_ticks: u32) -> PinDebouncer {
-        PinDebouncer {
-            current_index: 0,
-            last_debounced_state: BtnState::UnPressed,
-            debounced_state: BtnState::UnPressed,
-            press_ticks: press_ticks - 1,
-            repeat_ticks,
-            hold_ticks: hold_ticks - 1,
-            counter: 0,
-        }
+    /// Returns the state of the queried pin in the previous window along with its current state,
+    /// as returned by `get_state`, so the edges are consumed by this call as well. Useful to match
+    /// on `(from, to)` pairs, e.g. `(BtnState::Hold, BtnState::Repeat)`. Only the first `TRN`
+    /// pins are tracked, `Error::BtnUninitialized` is returned for the others
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn transition(&mut self, pin: usize) -> Result<(BtnState, BtnState), Error> {
+        Self::check_pin(pin)?;
+        let previous = self
+            .previous_states
+            .get(pin)
+            .copied()
+            .ok_or(Error::BtnUninitialized)?;
+        Ok((previous, self.get_state(pin)?))
     }
 
-    pub fn update(&mut self, pin_value: bool) -> bool {
-        if pin_value {
-            if self.counter < self.hold_ticks + self.repeat_ticks {
-                self.counter += 1;
-            }
-        } else {
-            self.counter = 0;
-        }
+    /// Maps the raw state of the queried pin to a user defined state, bypassing the built-in
+    /// `BtnState` thresholds. The closure is called with the counter of the pin, i.e. for how
+    /// many ticks it has been pressed, counted in steps of `N` at each window and zero while
+    /// released, and whether it was pressed or released in the last completed window. Repeats
+    /// consumed by `get_state` are subtracted from the counter
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    /// * `f` - The closure mapping the counter and the changed flag to the user state
+    pub fn classify<F, R>(&self, pin: usize, mut f: F) -> Result<R, Error>
+    where
+        F: FnMut(T, bool) -> R,
+    {
+        Self::check_pin(pin)?;
+        Ok(f(self.counter[pin], self.changed & W::bit(pin) != W::ZERO))
+    }
 
-        if self.current_index != self.press_ticks {
-            self.current_index += 1;
-            return false;
+    /// Returns whether the queried pin was released after reporting at least one `Repeat`, i.e.
+    /// a repeat burst just ended. The flag is cleared by this call, so it's only reported once
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn repeat_ended(&mut self, pin: usize) -> Result<bool, Error> {
+        Self::check_pin(pin)?;
+        let ended = self.repeat_ended & W::bit(pin) != W::ZERO;
+        self.repeat_ended &= !W::bit(pin);
+        Ok(ended)
+    }
+
+    /// Returns how the queried pin was released in the last completed window, telling a tap from
+    /// the release of a hold, i.e. whether it reached `hold_ticks` before being released. Returns
+    /// `None` if the pin wasn't released. The release is cleared by this call and at the next
+    /// window, so it's only reported once
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn release_kind(&mut self, pin: usize) -> Result<Option<ReleaseKind>, Error> {
+        Self::check_pin(pin)?;
+        if self.release_pending & W::bit(pin) == W::ZERO {
+            return Ok(None);
+        }
+        self.release_pending &= !W::bit(pin);
+        if self.long_released & W::bit(pin) != W::ZERO {
+            Ok(Some(ReleaseKind::LongHold))
+        } else {
+            Ok(Some(ReleaseKind::Tap))
+        }
+    }
+
+    /// Returns the number of `Repeat` states returned by `get_state` for the queried pin during
+    /// the current hold, i.e. the ordinal of the last repeat, for custom rate curves. It's reset
+    /// on release
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn repeat_index(&self, pin: usize) -> Result<u32, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.repeat_index[pin])
+    }
+
+    /// Returns whether the queried pin was released in the last completed window, i.e. once per
+    /// release. The flag is cleared by this call and at the next window
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn just_released(&mut self, pin: usize) -> Result<bool, Error> {
+        Self::check_pin(pin)?;
+        let released = self.just_released & W::bit(pin) != W::ZERO;
+        self.just_released &= !W::bit(pin);
+        Ok(released)
+    }
+
+    /// Returns the number of bounces of the queried pin, i.e. the number of times its raw sample
+    /// differed from the previous one within a window, since the creation or the last call to
+    /// `reset_bounce_counts`. Useful to characterize the switches when picking `N`. Only the
+    /// first `BNC` pins are counted, the others always read zero
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which count must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn bounce_count(&self, pin: usize) -> Result<u32, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.bounces.get(pin).copied().unwrap_or(0))
+    }
+
+    /// Clears the bounce counts of all the pins, see `bounce_count`
+    pub fn reset_bounce_counts(&mut self) {
+        for bounces in self.bounces.iter_mut() {
+            *bounces = 0;
+        }
+    }
+
+    /// Returns the number of presses, i.e. `ChangedToPressed` events, of all the enabled buttons
+    /// since the creation or the last call to `reset_counts`, e.g. for usage statistics. The
+    /// count saturates instead of wrapping
+    pub fn total_presses(&self) -> u32 {
+        self.total_presses
+    }
+
+    /// Same as `total_presses`, but for a single pin
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which count must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn presses(&self, pin: usize) -> Result<u32, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.presses[pin])
+    }
+
+    /// Clears the press counts, see `total_presses`
+    pub fn reset_counts(&mut self) {
+        self.total_presses = 0;
+        for presses in self.presses.iter_mut() {
+            *presses = 0;
+        }
+    }
+
+    /// Returns the latched toggle state of the queried pin, which starts off and is flipped on
+    /// each `ChangedToPressed`, for momentary buttons used as toggles. See `ToggleDebouncer` for
+    /// a single pin
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn toggled(&self, pin: usize) -> Result<bool, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.toggled & W::bit(pin) != W::ZERO)
+    }
+
+    /// Overrides the latched toggle state of the queried pin, e.g. to restore it at startup
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be set. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    /// * `on` - The new toggle state
+    pub fn set_toggled(&mut self, pin: usize, on: bool) -> Result<(), Error> {
+        Self::check_pin(pin)?;
+        if on {
+            self.toggled |= W::bit(pin);
+        } else {
+            self.toggled &= !W::bit(pin);
+        }
+        Ok(())
+    }
+
+    /// Returns the latched toggle state of all the buttons, where each bit is a button in the
+    /// same order as the `port_value` used in the `update` method, see `toggled`
+    pub fn toggled_mask(&self) -> W {
+        self.toggled & Self::btns_mask()
+    }
+
+    /// Returns whether the queried pin reached the hold state since the last call, i.e. once per
+    /// hold, unlike `get_state` which keeps reporting `Hold`. The flag is cleared by this call and
+    /// on release
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn hold_edge(&mut self, pin: usize) -> Result<bool, Error> {
+        Self::check_pin(pin)?;
+        let edge = self.hold_edge & W::bit(pin) != W::ZERO;
+        self.hold_edge &= !W::bit(pin);
+        Ok(edge)
+    }
+
+    /// Runs the `update` method over a recorded sequence of port values, querying the state of
+    /// every button each time a window is completed, the same way a live application would.
+    /// Returns the states from the last completed window, if no window was completed the states
+    /// are queried once at the end
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The port values, in the order they would have been passed to `update`
+    pub fn process_samples(&mut self, samples: &[W]) -> GenericArray<BtnState, BTNS> {
+        self.process_samples_with(samples, |_, _, _| {})
+    }
+
+    /// Same as `process_samples`, but also reports every intermediate transition. The closure is
+    /// called with the index of the sample which completed the window, the pin and its new state
+    /// each time the state of a pin differs from the one in the previous window, all the pins
+    /// start as `UnPressed`
+    pub fn process_samples_with<F>(
+        &mut self,
+        samples: &[W],
+        mut f: F,
+    ) -> GenericArray<BtnState, BTNS>
+    where
+        F: FnMut(usize, usize, BtnState),
+    {
+        let mut states: GenericArray<BtnState, BTNS> =
+            GenericArray::generate(|_| BtnState::UnPressed);
+        let mut completed = false;
+        for (index, &sample) in samples.iter().enumerate() {
+            if !self.update(sample) {
+                continue;
+            }
+            completed = true;
+            // The pins past the width of the port word are never pressed
+            for (pin, state) in states.iter_mut().enumerate().take(W::BITS) {
+                let new_state = self.get_state(pin).unwrap();
+                if new_state != *state {
+                    f(index, pin, new_state);
+                    *state = new_state;
+                }
+            }
+        }
+        if !completed {
+            for (pin, state) in states.iter_mut().enumerate().take(W::BITS) {
+                *state = self.get_state(pin).unwrap();
+            }
+        }
+        states
+    }
+
+    /// Feeds a new sample assembled from individual pin readings, for buttons read from several
+    /// sources instead of a single port. The named pins are set or cleared, as active-high
+    /// levels, and the others keep their last raw value, see `last_raw_sample`. Readings for pins
+    /// past the width of `W` are ignored
+    ///
+    /// Each call is a whole tick, same as `update`, so all the readings of a tick must be passed
+    /// at once, splitting them across calls would feed the window with extra, partial, samples.
+    /// Returns true when this call completed a window
+    ///
+    /// # Arguments
+    ///
+    /// * `readings` - Pairs of pin and pressed level
+    pub fn update_pins(&mut self, readings: &[(usize, bool)]) -> bool {
+        let mut port_value = self.last_raw_sample();
+        for &(pin, pressed) in readings {
+            if pressed {
+                port_value |= W::bit(pin);
+            } else {
+                port_value &= !W::bit(pin);
+            }
+        }
+        self.update(port_value)
+    }
+
+    /// Same as `update`, but when this call completes a window the closure is called with the
+    /// details of the window, see `UpdateInfo`, so custom logic can be built on top of the
+    /// debouncer in one pass. Returns true when this call completed a window
+    pub fn update_detailed<F: FnMut(UpdateInfo<W>)>(&mut self, port_value: W, mut f: F) -> bool {
+        let last_debounced_state = self.debounced_state;
+        if !self.update(port_value) {
+            return false;
+        }
+        f(UpdateInfo {
+            raw_and: self
+                .port_states
+                .iter()
+                .fold(W::ONES, |state, &sample| state & sample),
+            debounced_state: self.debounced_state,
+            last_debounced_state,
+            changed_to_pressed: self.changed_to_pressed,
+            changed_to_unpressed: self.changed & !self.changed_to_pressed,
+        });
+        true
+    }
+
+    /// Feeds the same port value `times` times, as when catching up after the main loop stalled.
+    /// Returns true if at least one window was completed, the states of the intermediate windows
+    /// are lost, see `update_n_with` to observe them
+    pub fn update_n(&mut self, port_value: W, times: usize) -> bool {
+        self.update_n_with(port_value, times, |_| {})
+    }
+
+    /// Same as `update_n`, but the closure is called with the debouncer each time a window is
+    /// completed, so the states can be queried as in a live application and no transition is
+    /// missed
+    pub fn update_n_with<F>(&mut self, port_value: W, times: usize, mut f: F) -> bool
+    where
+        F: FnMut(&mut Self),
+    {
+        let mut completed = false;
+        for _ in 0..times {
+            if self.update(port_value) {
+                completed = true;
+                f(self);
+            }
+        }
+        completed
+    }
+
+    /// Puts a button directly in a known state, as if it had been stable in that state for the
+    /// whole sampling window. The hold counter starts over and no `ChangedToPressed` is
+    /// generated, so the following `update` calls behave as for a button that had just settled
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be forced. Where the zeroth pin is considered to be the
+    ///   least significant bit in the `port_value` used in the `update` method
+    /// * `pressed` - The state to be forced
+    pub fn force_state(&mut self, pin: usize, pressed: bool) -> Result<(), Error> {
+        Self::check_pin(pin)?;
+        let mask = W::bit(pin);
+        let raw = if pressed ^ (self.normally_closed & mask != W::ZERO) {
+            mask
+        } else {
+            W::ZERO
+        };
+        for state in self.port_states.iter_mut() {
+            *state = (*state & !mask) | raw;
+        }
+        if (self.debounced_state & mask != W::ZERO) != pressed {
+            self.changed_at[pin] = self.tick;
+        }
+        if pressed {
+            self.debounced_state |= mask;
+            self.counter[pin] = self.pressed_ticks();
+            self.hold_duration[pin] = Self::window_ticks();
+        } else {
+            self.debounced_state &= !mask;
+            self.counter[pin] = T::ZERO;
+            self.hold_duration[pin] = T::ZERO;
+        }
+        self.last_debounced_state =
+            (self.last_debounced_state & !mask) | (self.debounced_state & mask);
+        self.changed_to_pressed &= !mask;
+        self.unread_presses &= !mask;
+        self.changed &= !mask;
+        self.repeating &= !mask;
+        self.repeat_rate[pin] = self.pin_repeat(pin);
+        self.repeat_index[pin] = 0;
+        Ok(())
+    }
+
+    /// Clears the debouncing state of a single button, leaving the others untouched. The button
+    /// is considered released, so if it's still held it's reported as a fresh press, with a
+    /// `ChangedToPressed`, at the next window. Useful to re-arm one-shot actions
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be reset. Where the zeroth pin is considered to be the
+    ///   least significant bit in the `port_value` used in the `update` method
+    pub fn reset_pin(&mut self, pin: usize) -> Result<(), Error> {
+        Self::check_pin(pin)?;
+        let mask = !W::bit(pin);
+        self.debounced_state &= mask;
+        self.last_debounced_state &= mask;
+        self.changed_to_pressed &= mask;
+        self.unread_presses &= mask;
+        self.changed &= mask;
+        self.repeating &= mask;
+        self.repeat_ended &= mask;
+        self.hold_edge &= mask;
+        self.counter[pin] = T::ZERO;
+        self.hold_duration[pin] = T::ZERO;
+        self.confirm_count[pin] = 0;
+        self.grace_count[pin] = 0;
+        self.repeat_rate[pin] = self.pin_repeat(pin);
+        self.repeat_index[pin] = 0;
+        if let Some(state) = self.window_states.get_mut(pin) {
+            *state = BtnState::UnPressed;
+            self.previous_states[pin] = BtnState::UnPressed;
+        }
+        Ok(())
+    }
+
+    /// Clears the hold and repeat counters of all the buttons while keeping their debounced
+    /// state and configuration. The buttons already pressed stay `Pressed`, without a new
+    /// `ChangedToPressed`, but must be held again for the whole hold time to reach `Hold`, a
+    /// pending `hold_edge` being dropped
+    pub fn clear_counters(&mut self) {
+        let pressed = self.pressed_ticks();
+        for index in 0..BTNS::USIZE {
+            if self.debounced_state & W::bit(index) != W::ZERO {
+                self.counter[index] = pressed;
+                self.hold_duration[index] = Self::window_ticks();
+            } else {
+                self.counter[index] = T::ZERO;
+                self.hold_duration[index] = T::ZERO;
+            }
+        }
+        self.reset_repeat_rates();
+        self.repeating = W::ZERO;
+        self.hold_edge = W::ZERO;
+    }
+
+    /// Returns whether the last call to `update` completed a window, same as its return value
+    pub fn window_complete(&self) -> bool {
+        self.warmed_up && self.current_index == 0
+    }
+
+    /// Returns the last port value passed to `update`, before any debouncing. Returns zero if
+    /// `update` was never called
+    pub fn last_raw_sample(&self) -> W {
+        self.port_states[(self.current_index + N::USIZE - 1) % N::USIZE]
+    }
+
+    /// Returns the last `N` raw samples passed to `update`, from the oldest to the newest, for
+    /// analysis or visualization of the input. Slots not written yet since the creation read as
+    /// zero
+    pub fn sample_history(&self) -> impl Iterator<Item = W> + '_ {
+        let (newer, older) = self.port_states.split_at(self.current_index);
+        older.iter().chain(newer.iter()).copied()
+    }
+
+    /// Writes the state of every button, as returned by `observe_state`, packed in 3 bits per
+    /// button for compact telemetry, so a pending `ChangedToPressed` or `Repeat` isn't consumed.
+    /// The zeroth button takes the three least significant bits of the first byte, the next ones
+    /// follow, crossing the byte boundaries, and each field holds the `BtnState` discriminant,
+    /// see `BtnState::from_u8`. The unused bits of the last byte are cleared. Returns the number
+    /// of bytes written, or `Error::BufferTooSmall` without writing anything
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The buffer, at least `(3 * BTNS + 7) / 8` bytes long, where `BTNS` is capped at
+    ///   the number of bits of the port word, as the pins past it are never packed
+    pub fn pack_states(&self, out: &mut [u8]) -> Result<usize, Error> {
+        let buttons = BTNS::USIZE.min(W::BITS);
+        let len = (3 * buttons).div_ceil(8);
+        if out.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+        for byte in out[..len].iter_mut() {
+            *byte = 0;
+        }
+        for pin in 0..buttons {
+            let state = self.observe_state(pin)? as u16;
+            let offset = 3 * pin;
+            let field = state << (offset % 8);
+            out[offset / 8] |= field as u8;
+            if field > 0xff {
+                out[offset / 8 + 1] |= (field >> 8) as u8;
+            }
+        }
+        Ok(len)
+    }
+
+    /// Returns an iterator over the state of every button, along with its pin, in the same order
+    /// as the bits of the `port_value` used in the `update` method. Unlike `get_state`, this
+    /// doesn't consume any state. `&PortDebouncer` implements `IntoIterator` the same way
+    pub fn states(&self) -> PortStates<'_, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> {
+        PortStates {
+            debouncer: self,
+            pin: 0,
+        }
+    }
+
+    /// Returns an iterator over the enabled pins which were pressed or released in the last
+    /// completed window, along with their new state, either `ChangedToPressed` or `UnPressed`.
+    /// Unlike `get_state`, this doesn't consume any state, so it's meant to be used right after
+    /// `update` completes a window
+    pub fn changed_iter(&self) -> impl Iterator<Item = (usize, BtnState)> + '_ {
+        let changed = self.changed & self.enabled;
+        (0..BTNS::USIZE)
+            .filter(move |&pin| changed & W::bit(pin) != W::ZERO)
+            .map(move |pin| {
+                if self.changed_to_pressed & W::bit(pin) != W::ZERO {
+                    (pin, BtnState::ChangedToPressed)
+                } else {
+                    (pin, BtnState::UnPressed)
+                }
+            })
+    }
+
+    /// Returns whether any enabled pin was pressed or released in the window completed by the last
+    /// call to `update`, as a cheap gate for event-driven redraws. Returns false once the next
+    /// window is in progress, i.e. it must be checked right after `update` returns true
+    pub fn changed_this_window(&self) -> bool {
+        self.current_index == 0 && self.changed & self.enabled != W::ZERO
+    }
+
+    /// Returns the enabled pins which changed to pressed in the last completed window, one bit per
+    /// pin. The pins set together were pressed within the same window, i.e. within `N` ticks of
+    /// each other, as for chords, while pins pressed in different windows are reported by
+    /// different calls. Unlike `get_state`, this doesn't consume any state
+    pub fn simultaneous_presses(&self) -> W {
+        self.changed_to_pressed & self.enabled & Self::btns_mask()
+    }
+
+    /// Returns whether both queried pins changed to pressed in the last completed window, see
+    /// `simultaneous_presses`
+    ///
+    /// # Arguments
+    ///
+    /// * `a`, `b` - Pins to be checked. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn pressed_together(&self, a: usize, b: usize) -> Result<bool, Error> {
+        Self::check_pin(a)?;
+        Self::check_pin(b)?;
+        let mask = W::bit(a) | W::bit(b);
+        Ok(self.simultaneous_presses() & mask == mask)
+    }
+
+    /// Returns the enabled pins which were pressed or released, as reported by `get_state`, in
+    /// any window completed since the last call to this method, and clears them. Unlike polling
+    /// `get_state`, no change is missed when several windows complete between two calls
+    pub fn take_changed(&mut self) -> W {
+        core::mem::replace(&mut self.sticky_changed, W::ZERO)
+    }
+
+    /// Returns the debounced port state computed only over the samples of the window still in
+    /// progress, ignoring the stale slots left by the previous window. This trades precision for
+    /// latency, as fewer samples are required to agree. At the start of a window, before any
+    /// sample was taken, the debounced state of the last completed window is returned
+    pub fn partial_window(&self) -> W {
+        if self.current_index == 0 {
+            return self.debounced_state;
+        }
+        self.port_states[..self.current_index]
+            .iter()
+            .fold(W::ONES, |state, &sample| {
+                state & (sample ^ self.normally_closed)
+            })
+    }
+
+    /// Returns for how many ticks the queried pin has been pressed, in multiples of `N`. The
+    /// measurement stops at the value set by `set_max_hold_ticks`, which defaults to the repeat
+    /// threshold, and it isn't affected by `Repeat` states. Returns zero for a released pin
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn held_ticks(&self, pin: usize) -> Result<T, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.hold_duration[pin])
+    }
+
+    /// Returns how many more ticks the queried pin must stay pressed to reach the hold state,
+    /// e.g. for a countdown UI, zero once it's reached. The state is updated once per window, so
+    /// the value drops in steps of `N` and `Hold` is reported at the window where it reaches zero
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn ticks_until_hold(&self, pin: usize) -> Result<T, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.pin_hold(pin).saturating_sub(self.counter[pin]))
+    }
+
+    /// Returns how many more ticks the queried pin must stay pressed to reach the next `Repeat`,
+    /// same as `ticks_until_hold`. While repeat is disabled, see `set_repeat_enabled`, the value
+    /// stops at the repeat interval
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn ticks_until_repeat(&self, pin: usize) -> Result<T, Error> {
+        Self::check_pin(pin)?;
+        Ok(self
+            .pin_hold(pin)
+            .saturating_add(self.repeat_rate[pin])
+            .saturating_sub(self.counter[pin]))
+    }
+
+    /// Returns how far the queried pin is from the hold state to the first repeat, scaled from
+    /// zero at the hold threshold up to 255 at the repeat threshold, handy to drive an animation
+    /// or a PWM duty cycle. Returns zero before the hold state
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn hold_level(&self, pin: usize) -> Result<u8, Error> {
+        Self::check_pin(pin)?;
+        Ok(scale_level(
+            self.hold_duration[pin].to_u64(),
+            self.pin_hold(pin).to_u64(),
+            self.pin_repeat(pin).to_u64(),
+        ))
+    }
+
+    /// Returns for how many ticks the queried pin was pressed in its last completed press, in
+    /// multiples of `N`, latched when it was released. It's measured as in `held_ticks`, so it
+    /// has the same clamp. Returns zero if the pin was never released
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn last_press_duration(&self, pin: usize) -> Result<T, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.last_press_duration[pin])
+    }
+
+    /// Returns whether the queried pin has been pressed for longer than `threshold_ticks`, which
+    /// may indicate a hardware fault such as a shorted button. The duration is the one reported by
+    /// `held_ticks`, so `set_max_hold_ticks` must be used to raise the measurement clamp above the
+    /// threshold. A stuck pin can be excluded with `set_enabled_mask` until it clears
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    /// * `threshold_ticks` - The longest legitimate hold, in ticks
+    pub fn is_stuck(&self, pin: usize, threshold_ticks: T) -> Result<bool, Error> {
+        Ok(self.held_ticks(pin)? > threshold_ticks)
+    }
+
+    /// Returns whether at least one full window of `N` samples was completed. Before that, all
+    /// the pins are reported as `UnPressed` regardless of their input
+    pub fn is_warmed_up(&self) -> bool {
+        self.warmed_up
+    }
+
+    /// Returns the number of ticks, i.e. calls to the `update` method, since the debounced state
+    /// of the queried pin last changed, either from released to pressed or the other way around.
+    /// It's computed from the tick of the last change, so it wraps around after `u32::MAX` ticks
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn ticks_since_change(&self, pin: usize) -> Result<u32, Error> {
+        Self::check_pin(pin)?;
+        Ok(self.tick.wrapping_sub(self.changed_at[pin]))
+    }
+
+    /// Returns the number of buttons debounced, i.e. `BTNS`, so generic code can loop over the
+    /// pins without naming the type parameters
+    pub fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    /// Returns the number of samples in a window, i.e. `N`
+    pub fn sample_window(&self) -> usize {
+        N::USIZE
+    }
+
+    /// Bit mask of the initialized pins
+    fn btns_mask() -> W {
+        W::low_bits(BTNS::USIZE)
+    }
+
+    /// Number of ticks in a window, i.e. `N`, by which the counters advance at each window
+    fn window_ticks() -> T {
+        T::from_usize(N::USIZE)
+    }
+
+    /// Counter of a button pressed for a single window, i.e. past the `min_press_ticks` filter
+    fn pressed_ticks(&self) -> T {
+        T::from_usize(self.min_press_ticks).max(Self::window_ticks())
+    }
+
+    /// Returns an iterator over the buttons whose bits are set in `mask`, from the lowest one,
+    /// visiting only the bits set instead of every button
+    fn pins_in(mask: W) -> impl Iterator<Item = usize> {
+        let mut mask = mask & Self::btns_mask();
+        core::iter::from_fn(move || {
+            if mask == W::ZERO {
+                None
+            } else {
+                let pin = mask.trailing_zeros() as usize;
+                mask = mask ^ W::bit(pin);
+                Some(pin)
+            }
+        })
+    }
+
+    /// Returns the pressed level of every enabled button, from the last completed window, as a
+    /// `u32` shifted left by `shift`, so the masks of several debouncers handling separate banks
+    /// can be ORed into one logical port. Bits shifted past the width of `u32` are dropped
+    ///
+    /// # Arguments
+    ///
+    /// * `shift` - The logical pin of the zeroth button of this debouncer
+    pub fn shifted_state(&self, shift: u32) -> u32 {
+        Self::shift_mask(self.debounced_state & self.enabled, shift)
+    }
+
+    /// Same as `shifted_state`, but for the enabled buttons which were pressed or released in
+    /// the last completed window
+    ///
+    /// # Arguments
+    ///
+    /// * `shift` - The logical pin of the zeroth button of this debouncer
+    pub fn shifted_changed(&self, shift: u32) -> u32 {
+        Self::shift_mask(self.changed & self.enabled, shift)
+    }
+
+    fn shift_mask(mask: W, shift: u32) -> u32 {
+        (0..BTNS::USIZE)
+            .filter(|&pin| mask & W::bit(pin) != W::ZERO)
+            .filter_map(|pin| 1u32.checked_shl((pin as u32).saturating_add(shift)))
+            .fold(0, |shifted, bit| shifted | bit)
+    }
+
+    /// Validates a pin index given to a public method, rejecting the pins past `BTNS` and the
+    /// ones which don't fit in the port word
+    fn check_pin(pin: usize) -> Result<(), Error> {
+        if pin >= BTNS::USIZE || pin >= W::BITS {
+            Err(Error::BtnUninitialized)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the enabled buttons currently pressed, according to the last completed window
+    fn enabled_pressed(&self) -> W {
+        self.debounced_state & self.enabled & Self::btns_mask()
+    }
+
+    /// Returns the number of enabled buttons currently pressed, according to the last completed
+    /// window
+    pub fn pressed_count(&self) -> u32 {
+        self.enabled_pressed().count_ones()
+    }
+
+    /// Returns whether any of the enabled buttons is currently pressed, according to the last
+    /// completed window
+    pub fn any_pressed(&self) -> bool {
+        self.enabled_pressed() != W::ZERO
+    }
+
+    /// Returns whether any of the buttons in `mask` is currently pressed, according to the last
+    /// completed window, e.g. for a group of buttons such as a D-pad
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Bit mask where the bits set represent the buttons of the group
+    pub fn any_pressed_in(&self, mask: W) -> bool {
+        self.enabled_pressed() & mask != W::ZERO
+    }
+
+    /// Returns whether all the buttons in `mask` are currently pressed, according to the last
+    /// completed window. Disabled buttons and bits past the initialized ones are never pressed,
+    /// and an empty mask is always satisfied
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - Bit mask where the bits set represent the buttons of the group
+    pub fn all_pressed_in(&self, mask: W) -> bool {
+        self.enabled_pressed() & mask == mask
+    }
+
+    /// Returns the lowest enabled button currently pressed, according to the last completed
+    /// window, or `None` if none is pressed, e.g. for a "press any key" prompt
+    pub fn first_pressed(&self) -> Option<usize> {
+        Self::pins_in(self.enabled_pressed()).next()
+    }
+
+    /// Returns the lowest enabled button which changed to pressed in the last completed window,
+    /// or `None` if none did, i.e. which key was just pressed. Unlike `get_state`, this doesn't
+    /// consume any state
+    pub fn first_changed_to_pressed(&self) -> Option<usize> {
+        Self::pins_in(self.changed_to_pressed & self.enabled).next()
+    }
+
+    /// Returns an iterator over the codes of the enabled buttons currently pressed, e.g. to build
+    /// a HID keyboard report. The code of a button is taken from `map` at the button index,
+    /// buttons without an entry in `map` are skipped
+    ///
+    /// # Arguments
+    ///
+    /// * `map` - Code of each button, a `GenericArray<u8, BTNS>` can be passed as well
+    pub fn pressed_keycodes<'a>(&'a self, map: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+        let pressed = self.enabled_pressed();
+        map.iter()
+            .take(BTNS::USIZE)
+            .enumerate()
+            .filter(move |&(pin, _)| pressed & W::bit(pin) != W::ZERO)
+            .map(|(_, &code)| code)
+    }
+
+    /// Returns a copy of the debounced output of the whole port, which can be compared against an
+    /// expected value instead of querying every pin
+    pub fn snapshot(&self) -> PortSnapshot<BTNS, W, T> {
+        PortSnapshot {
+            debounced_state: self.debounced_state,
+            changed_to_pressed: self.changed_to_pressed,
+            counters: self.counter.clone(),
+        }
+    }
+
+    /// Returns the per pin counters, i.e. for how many ticks each button has been pressed,
+    /// counted in steps of `N` at each window. Together with `debounced_state` and
+    /// `last_debounced_state`, this is the context to save before a reconfiguration or a
+    /// low-power transition, so `restore_counters` can resume the debouncing without spurious
+    /// edges
+    pub fn counters(&self) -> &GenericArray<T, BTNS> {
+        &self.counter
+    }
+
+    /// Replaces the per pin counters with ones saved by `counters`. The pending edges and repeats
+    /// aren't restored, so the next window reports the buttons as continuing their press
+    ///
+    /// # Arguments
+    ///
+    /// * `counters` - The counters returned by `counters`
+    pub fn restore_counters(&mut self, counters: &GenericArray<T, BTNS>) {
+        self.counter.clone_from(counters);
+    }
+
+    /// Returns the debounced state of the port from the last completed window, one bit per pin
+    pub fn debounced_state(&self) -> W {
+        self.debounced_state
+    }
+
+    /// Replaces the debounced state of the port, e.g. with one saved by `debounced_state`. This
+    /// doesn't generate any edge, use `force_state` to put a single button in a known state
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Bit mask where the bits set represent the pressed pins
+    pub fn set_debounced_state(&mut self, state: W) {
+        self.debounced_state = state;
+    }
+
+    /// Returns the debounced state of the port the next window is compared against to detect the
+    /// edges, one bit per pin. It's the same as `debounced_state` once a window is completed
+    pub fn last_debounced_state(&self) -> W {
+        self.last_debounced_state
+    }
+
+    /// Replaces the debounced state the next window is compared against, e.g. with one saved by
+    /// `last_debounced_state`, so the buttons pressed before a save aren't reported as new presses
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - Bit mask where the bits set represent the pressed pins
+    pub fn set_last_debounced_state(&mut self, state: W) {
+        self.last_debounced_state = state;
+    }
+
+    /// Returns the debouncing period in milliseconds, i.e. the time a pin must be stable before
+    /// its debounced state changes
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_period_ms` - The period at which the `update` method is called
+    pub fn debounce_period_ms(&self, tick_period_ms: u32) -> u32 {
+        (N::USIZE as u32).saturating_mul(tick_period_ms)
+    }
+
+    /// Returns the time in milliseconds a button must be held to reach the hold state, as set for
+    /// the whole port, i.e. not taking `set_all_timings` into account. The hold state is reported
+    /// at the end of a window, so this differs from the time passed to `new` if that one wasn't a
+    /// multiple of `N`
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_period_ms` - The period at which the `update` method is called
+    pub fn hold_period_ms(&self, tick_period_ms: u32) -> u32 {
+        (self.hold_ticks.div_ceil(N::USIZE) as u32)
+            .saturating_mul(N::U32)
+            .saturating_mul(tick_period_ms)
+    }
+
+    /// Returns the average time in milliseconds between the hold state and the first repeat, as
+    /// set for the whole port, i.e. not taking `set_all_timings` into account
+    ///
+    /// # Arguments
+    ///
+    /// * `tick_period_ms` - The period at which the `update` method is called
+    pub fn repeat_period_ms(&self, tick_period_ms: u32) -> u32 {
+        (self.repeat_ticks as u32).saturating_mul(tick_period_ms)
+    }
+}
+
+/// Iterator over the state of every button of a `PortDebouncer`, as returned by `observe_state`,
+/// along with its pin. See `PortDebouncer::states`
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct PortStates<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    debouncer: &'a PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>,
+    pin: usize,
+}
+
+#[allow(deprecated)]
+impl<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> Iterator
+    for PortStates<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    type Item = (usize, BtnState);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pin = self.pin;
+        let state = self.debouncer.observe_state(pin).ok()?;
+        self.pin += 1;
+        Some((pin, state))
+    }
+}
+
+#[allow(deprecated)]
+impl<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> IntoIterator
+    for &'a PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    type Item = (usize, BtnState);
+    type IntoIter = PortStates<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.states()
+    }
+}
+
+#[derive(Debug)]
+pub struct PinDebouncer<T: Ticks = u32> {
+    current_index: T,
+    warmed_up: bool,
+    window_completed: bool,
+    last_debounced_state: BtnState,
+    debounced_state: BtnState,
+    previous_state: BtnState,
+    press_ticks: T,
+    release_ticks: T,
+    grace_ticks: T,
+    repeat_ticks: T,
+    initial_repeat_ticks: T,
+    min_repeat_ticks: T,
+    repeat_step: T,
+    repeat_interval_ticks: Option<T>,
+    repeat_enabled: bool,
+    hold_ticks: T,
+    min_press_ticks: T,
+    max_hold_ticks: Option<T>,
+    priority: PriorityMode,
+    repeating: bool,
+    repeat_ended: bool,
+    hold_reached: bool,
+    hold_edge: bool,
+    just_released: bool,
+    release_kind: Option<ReleaseKind>,
+    repeat_index: u32,
+    last_sample: bool,
+    last_tick: Option<u32>,
+    active_low: bool,
+    counter: T,
+    hold_duration: T,
+    low_ticks: T,
+    release_duration: T,
+    last_press_duration: T,
+    ticks_since_change: T,
+}
+
+impl PinDebouncer {
+    pub const fn new(press_ticks: u32, repeat_ticks: u32, hold_ticks: u32) -> PinDebouncer {
+        PinDebouncer::init(
+            press_ticks.saturating_sub(1),
+            press_ticks,
+            repeat_ticks,
+            hold_ticks.saturating_sub(1),
+        )
+    }
+
+    /// Same as `new`, but for an active-low pin, i.e. pressed when low. The values passed to
+    /// `update` are negated internally, so they can be read straight from the pin
+    pub const fn new_active_low(
+        press_ticks: u32,
+        repeat_ticks: u32,
+        hold_ticks: u32,
+    ) -> PinDebouncer {
+        PinDebouncer {
+            active_low: true,
+            ..PinDebouncer::new(press_ticks, repeat_ticks, hold_ticks)
+        }
+    }
+
+    /// Returns a PinDebouncer struct configured from the named fields of `config`, every field
+    /// being applied as by the matching setter, see `PinConfig`
+    pub fn from_config(config: PinConfig) -> PinDebouncer {
+        let mut debouncer = Self::new(config.press_ticks, config.repeat_ticks, config.hold_ticks);
+        debouncer.set_min_press_ticks(config.min_press_ticks);
+        if let Some(max_hold_ticks) = config.max_hold_ticks {
+            debouncer.set_max_hold_ticks(max_hold_ticks);
+        }
+        if let Some(interval) = config.repeat_interval_ticks {
+            debouncer.set_repeat_interval_ticks(interval);
+        }
+        if let Some(release_ticks) = config.release_ticks {
+            debouncer.set_release_ticks(release_ticks);
+        }
+        debouncer.set_priority_mode(config.priority);
+        debouncer
+    }
+}
+
+impl<T: Ticks> PinDebouncer<T> {
+    /// Returns a PinDebouncer struct with the given thresholds, already offset by one where
+    /// needed, and everything else at its default. Shared by the constructors of every `T`
+    const fn init(press_ticks: T, release_ticks: T, repeat_ticks: T, hold_ticks: T) -> Self {
+        PinDebouncer {
+            current_index: T::ZERO,
+            warmed_up: false,
+            window_completed: false,
+            last_debounced_state: BtnState::UnPressed,
+            debounced_state: BtnState::UnPressed,
+            previous_state: BtnState::UnPressed,
+            press_ticks,
+            release_ticks,
+            grace_ticks: T::ZERO,
+            repeat_ticks,
+            initial_repeat_ticks: repeat_ticks,
+            min_repeat_ticks: repeat_ticks,
+            repeat_step: T::ZERO,
+            repeat_interval_ticks: None,
+            repeat_enabled: true,
+            hold_ticks,
+            min_press_ticks: T::ZERO,
+            max_hold_ticks: None,
+            priority: PriorityMode::EdgeFirst,
+            repeating: false,
+            repeat_ended: false,
+            hold_reached: false,
+            hold_edge: false,
+            just_released: false,
+            release_kind: None,
+            repeat_index: 0,
+            last_sample: false,
+            last_tick: None,
+            active_low: false,
+            counter: T::ZERO,
+            hold_duration: T::ZERO,
+            low_ticks: T::ZERO,
+            release_duration: T::ZERO,
+            last_press_duration: T::ZERO,
+            ticks_since_change: T::ZERO,
+        }
+    }
+
+    /// Same as `new`, but counting the ticks with any `Ticks` type, e.g. a `u64` to measure
+    /// longer holds or a `u16` to save memory
+    pub fn with_ticks(press_ticks: T, repeat_ticks: T, hold_ticks: T) -> PinDebouncer<T> {
+        PinDebouncer::init(
+            press_ticks.saturating_sub(T::ONE),
+            press_ticks,
+            repeat_ticks,
+            hold_ticks.saturating_sub(T::ONE),
+        )
+    }
+
+    /// Same as `new_active_low`, but counting the ticks with any `Ticks` type, see `with_ticks`
+    pub fn with_ticks_active_low(
+        press_ticks: T,
+        repeat_ticks: T,
+        hold_ticks: T,
+    ) -> PinDebouncer<T> {
+        PinDebouncer {
+            active_low: true,
+            ..PinDebouncer::with_ticks(press_ticks, repeat_ticks, hold_ticks)
+        }
+    }
+
+    /// Sets the minimum number of ticks the pin must be held before it's reported as pressed.
+    /// Shorter contacts are reported as `UnPressed` and never generate a `ChangedToPressed`. This
+    /// is meant to filter accidental touches and defaults to zero, i.e. no filtering
+    pub fn set_min_press_ticks(&mut self, min_press_ticks: T) {
+        self.min_press_ticks = min_press_ticks;
+    }
+
+    /// Makes the repeat rate speed up the longer the pin is held. The first `Repeat` of a hold
+    /// happens `initial_ticks` after the hold state, and each following `Repeat` comes `step`
+    /// ticks sooner than the previous one, until the interval reaches `min_ticks`. The rate goes
+    /// back to `initial_ticks` when the pin is released. `initial_ticks` replaces the
+    /// `repeat_ticks` given in `new`
+    pub fn set_repeat_acceleration(&mut self, initial_ticks: T, min_ticks: T, step: T) {
+        self.repeat_interval_ticks = None;
+        self.repeat_ticks = initial_ticks;
+        self.initial_repeat_ticks = initial_ticks;
+        self.min_repeat_ticks = min_ticks;
+        self.repeat_step = step;
+    }
+
+    /// Enables or disables the repeat state. While disabled, the counter stops at the hold
+    /// threshold and `get_state` keeps reporting `Hold` for as long as the pin is held, never
+    /// `Repeat`. Enabled by default, a repeat interval of zero ticks disables it as well
+    pub fn set_repeat_enabled(&mut self, enabled: bool) {
+        self.repeat_enabled = enabled;
+    }
+
+    /// Sets the interval between repeats, following the keyboard "delay + rate" model: the first
+    /// `Repeat` of a hold comes `repeat_ticks` after the hold state, as given to `new`, and the
+    /// following ones every `repeat_interval_ticks`. By default the interval is the same as the
+    /// first delay. This replaces the acceleration set by `set_repeat_acceleration`
+    pub fn set_repeat_interval_ticks(&mut self, repeat_interval_ticks: T) {
+        self.repeat_interval_ticks = Some(repeat_interval_ticks);
+    }
+
+    /// Sets how long a hold can be measured by `held_ticks`, which by default stops counting at
+    /// the repeat threshold (`hold_ticks + repeat_ticks`). This doesn't change when the hold and
+    /// repeat states are reported, it only allows measuring longer holds
+    pub fn set_max_hold_ticks(&mut self, max_hold_ticks: T) {
+        self.max_hold_ticks = Some(max_hold_ticks);
+    }
+
+    /// Sets which state is reported when the pin is both just pressed and already in the hold or
+    /// repeat state, see `PriorityMode`
+    pub fn set_priority_mode(&mut self, priority: PriorityMode) {
+        self.priority = priority;
+    }
+
+    /// Sets how many consecutive low samples release a debounced press, defaults to `press_ticks`.
+    /// Shorter dips only take one tick off the counter per low sample, so a brief glitch during a
+    /// hold doesn't drop the button. One releases the pin on the first low sample. A press not
+    /// debounced yet still starts over on any low sample
+    pub fn set_release_ticks(&mut self, release_ticks: T) {
+        self.release_ticks = release_ticks.max(T::ONE);
+    }
+
+    /// Sets a grace period after a release during which a re-press continues the hold instead of
+    /// being a new press, e.g. for a finger briefly losing contact. The pin is only released after
+    /// `release_ticks + grace_ticks` consecutive low samples, and meanwhile the counter is held
+    /// instead of losing a tick per low sample, so the hold resumes where it was. Defaults to zero
+    pub fn set_grace_ticks(&mut self, grace_ticks: T) {
+        self.grace_ticks = grace_ticks;
+    }
+
+    /// Feeds a new sample of the pin, this method should be called every tick. Returns true when
+    /// this call completed a window of `press_ticks` samples, i.e. the debounced state was just
+    /// updated and should be queried. It doesn't mean that the pin changed its state
+    #[must_use]
+    pub fn update(&mut self, pin_value: bool) -> bool {
+        self.ticks_since_change = self.ticks_since_change.saturating_add(T::ONE);
+        self.last_sample = pin_value;
+        let pin_value = pin_value != self.active_low;
+        let max_hold = self.max_hold_ticks.unwrap_or(
+            self.hold_ticks
+                .saturating_add(T::ONE)
+                .saturating_add(self.initial_repeat_ticks),
+        );
+        if pin_value {
+            self.low_ticks = T::ZERO;
+            if self.hold_duration < max_hold {
+                self.hold_duration = self.hold_duration.saturating_add(T::ONE);
+            }
+            let repeat_ticks = if self.repeat_enabled {
+                self.repeat_ticks
+            } else {
+                T::ZERO
+            };
+            if self.counter < self.hold_ticks.saturating_add(repeat_ticks) {
+                self.counter = self.counter.saturating_add(T::ONE);
+            }
+        } else {
+            self.low_ticks = self.low_ticks.saturating_add(T::ONE);
+            // A press not debounced yet needs `press_ticks` consecutive highs, so any low sample
+            // starts it over, only a debounced press is released by the countdown
+            if self.last_debounced_state == BtnState::UnPressed
+                || self.low_ticks >= self.release_ticks.saturating_add(self.grace_ticks)
+            {
+                if self.hold_duration != T::ZERO {
+                    self.release_duration = self.hold_duration;
+                }
+                self.hold_duration = T::ZERO;
+                self.counter = T::ZERO;
+                self.repeat_ticks = self.initial_repeat_ticks;
+            } else if self.grace_ticks == T::ZERO {
+                self.counter = self.counter.saturating_sub(T::ONE);
+            }
+        }
+
+        if self.current_index != self.press_ticks {
+            self.current_index = self.current_index.saturating_add(T::ONE);
+            self.window_completed = false;
+            return false;
+        }
+
+        self.current_index = T::ZERO;
+        self.warmed_up = true;
+        self.window_completed = true;
+        if self.counter >= self.press_ticks && self.counter >= self.min_press_ticks {
+            self.debounced_state = BtnState::Pressed;
+        } else {
+            self.debounced_state = BtnState::UnPressed;
+        }
+        let changed_to_pressed = (self.last_debounced_state == BtnState::UnPressed)
+            && (self.debounced_state == BtnState::Pressed);
+        let duration_state = if self.repeat_enabled
+            && self.repeat_ticks != T::ZERO
+            && self.counter >= self.hold_ticks.saturating_add(self.repeat_ticks)
+        {
+            Some(BtnState::Repeat)
+        } else if self.counter >= self.hold_ticks {
+            Some(BtnState::Hold)
+        } else {
+            None
+        };
+        match (changed_to_pressed, duration_state) {
+            (true, Some(state)) if self.priority == PriorityMode::DurationFirst => {
+                self.debounced_state = state;
+            }
+            (true, _) => self.debounced_state = BtnState::ChangedToPressed,
+            (false, Some(state)) => self.debounced_state = state,
+            (false, None) => {}
+        }
+        if (self.last_debounced_state == BtnState::UnPressed)
+            != (self.debounced_state == BtnState::UnPressed)
+        {
+            self.ticks_since_change = T::ZERO;
+        }
+        if self.repeating && self.debounced_state == BtnState::UnPressed {
+            self.repeating = false;
+            self.repeat_ended = true;
+        }
+        self.just_released = self.last_debounced_state != BtnState::UnPressed
+            && self.debounced_state == BtnState::UnPressed;
+        self.release_kind = match (self.just_released, self.hold_reached) {
+            (false, _) => None,
+            (true, false) => Some(ReleaseKind::Tap),
+            (true, true) => Some(ReleaseKind::LongHold),
+        };
+        if self.just_released {
+            self.last_press_duration = self.release_duration;
+        }
+        if self.debounced_state == BtnState::UnPressed {
+            self.hold_reached = false;
+            self.repeat_index = 0;
+            self.hold_edge = false;
+        } else if self.counter >= self.hold_ticks && !self.hold_reached {
+            self.hold_reached = true;
+            self.hold_edge = true;
+        }
+        self.previous_state = self.last_debounced_state;
+        self.last_debounced_state = self.debounced_state;
+        true
+    }
+
+    /// Feeds a new sample of the pin taken at the absolute tick number `tick`, for loops whose
+    /// period varies. The ticks skipped since the previous call are first filled with the sample
+    /// of that call, as the pin is assumed to keep its level between samples, so the window stays
+    /// time-correct. The tick number is allowed to wrap around, and the first call only records
+    /// the sample. A sample taken at the same tick as the previous one, or at a tick behind it,
+    /// is ignored, leaving the state untouched. A tick more than `u32::MAX / 2` ahead of the
+    /// previous one is taken as one behind it, e.g. read from a racing timer. Skipped ticks cost
+    /// one call to `update` each, up to the ones needed for the counters to saturate, see
+    /// `ClockedPinDebouncer` to derive the ticks from a clock instead. Returns true if any window
+    /// was completed
+    pub fn update_at(&mut self, pressed: bool, tick: u32) -> bool {
+        let mut completed = false;
+        if let Some(last_tick) = self.last_tick {
+            let elapsed = tick.wrapping_sub(last_tick);
+            if elapsed == 0 || elapsed > u32::MAX / 2 {
+                return false;
+            }
+            completed = self.replay(self.last_sample, u64::from(elapsed - 1));
+        }
+        self.last_tick = Some(tick);
+        completed | self.update(pressed)
+    }
+
+    /// Feeds `pin_value` for `ticks` ticks, as that many calls to `update`. Once the counters
+    /// have saturated the following windows repeat the same state, so only the ticks needed to
+    /// get there are fed, keeping the position in the window, and the others are only added to
+    /// the measured durations. Returns true if any window was completed
+    fn replay(&mut self, pin_value: bool, ticks: u64) -> bool {
+        let window = self.press_ticks.to_u64().saturating_add(1);
+        let limit = self
+            .hold_ticks
+            .to_u64()
+            .saturating_add(self.repeat_ticks.to_u64())
+            .saturating_add(self.release_ticks.to_u64())
+            .saturating_add(self.grace_ticks.to_u64())
+            .saturating_add(window.saturating_mul(2));
+        let fed = if ticks > limit {
+            limit + (ticks - limit) % window
+        } else {
+            ticks
+        };
+        let mut completed = false;
+        for _ in 0..fed {
+            completed |= self.update(pin_value);
+        }
+
+        let skipped =
+            T::from_usize(core::convert::TryFrom::try_from(ticks - fed).unwrap_or(usize::MAX));
+        if skipped != T::ZERO {
+            self.ticks_since_change = self.ticks_since_change.saturating_add(skipped);
+            if pin_value != self.active_low {
+                let max_hold = self.max_hold_ticks.unwrap_or(
+                    self.hold_ticks
+                        .saturating_add(T::ONE)
+                        .saturating_add(self.initial_repeat_ticks),
+                );
+                if self.hold_duration < max_hold {
+                    self.hold_duration = self.hold_duration.saturating_add(skipped).min(max_hold);
+                }
+            } else {
+                self.low_ticks = self.low_ticks.saturating_add(skipped);
+            }
+        }
+        completed
+    }
+
+    /// Feeds a new sample of the pin and, when this completes a window, returns its state as
+    /// `get_state` does, consuming a pending `Repeat`. Returns `None` in the middle of a window,
+    /// as the state only changes at the window boundaries
+    pub fn tick(&mut self, pressed: bool) -> Option<BtnState> {
+        if self.update(pressed) {
+            Some(self.get_state())
+        } else {
+            None
+        }
+    }
+
+    /// Feeds a new sample of the pin and always returns its state, so it doesn't have to be polled
+    /// only when `update` returns true. At the end of a window the state is returned as by
+    /// `get_state`, consuming a pending `ChangedToPressed` or `Repeat`. In the middle of a window it's
+    /// the best effort state, recomputed from the samples of the partial window: a press is
+    /// reported as `ChangedToPressed` as soon as the counter holds a whole window of pressed
+    /// samples, e.g. from a press started in the previous window, the end of the window then
+    /// reporting it as `Pressed`, and a release as soon as the counter is cleared. The duration
+    /// states are the ones of the last completed window
+    ///
+    /// This trades some accuracy in the middle of a window for simplicity and a lower latency, as
+    /// the state is recomputed every tick
+    pub fn update_and_state(&mut self, pressed: bool) -> BtnState {
+        if self.update(pressed) {
+            self.get_state()
+        } else if self.counter == T::ZERO {
+            BtnState::UnPressed
+        } else if self.last_debounced_state == BtnState::UnPressed
+            && self.counter > self.press_ticks
+            && self.counter >= self.min_press_ticks
+        {
+            // The edge is taken now, so the end of the window sees the pin as already pressed
+            self.ticks_since_change = T::ZERO;
+            self.debounced_state = BtnState::Pressed;
+            self.last_debounced_state = BtnState::Pressed;
+            BtnState::ChangedToPressed
+        } else {
+            self.observe_state()
+        }
+    }
+
+    /// Returns the state of the pin according to the last completed window. A `ChangedToPressed`
+    /// or a `Repeat` is consumed by this call, a second call in the same window returns `Pressed`
+    /// or `Hold` instead, so each edge is only reported once. `observe_state` can be used for any
+    /// additional read
+    pub fn get_state(&mut self) -> BtnState {
+        match self.debounced_state {
+            BtnState::ChangedToPressed => {
+                self.debounced_state = BtnState::Pressed;
+                BtnState::ChangedToPressed
+            }
+            BtnState::Repeat => {
+                // The counter may have been reset by a low sample since the window completed
+                self.counter = self.counter.saturating_sub(self.repeat_ticks);
+                self.repeat_ticks = match self.repeat_interval_ticks {
+                    Some(interval) => interval,
+                    None => self
+                        .repeat_ticks
+                        .saturating_sub(self.repeat_step)
+                        .max(self.min_repeat_ticks),
+                };
+                self.debounced_state = BtnState::Hold;
+                self.repeating = true;
+                self.repeat_index = self.repeat_index.saturating_add(1);
+                BtnState::Repeat
+            }
+            other => other,
+        }
+    }
+
+    /// Returns the state of the pin in the previous window along with its current state, as
+    /// returned by `get_state`, so the edges are consumed by this call as well. Useful to match on
+    /// `(from, to)` pairs, e.g. `(BtnState::Hold, BtnState::Repeat)`
+    pub fn transition(&mut self) -> (BtnState, BtnState) {
+        let state = self.get_state();
+        (self.previous_state, state)
+    }
+
+    /// Returns whether the pin was released after reporting at least one `Repeat`, i.e. a repeat
+    /// burst just ended. The flag is cleared by this call, so it's only reported once
+    pub fn repeat_ended(&mut self) -> bool {
+        let ended = self.repeat_ended;
+        self.repeat_ended = false;
+        ended
+    }
+
+    /// Returns how the pin was released in the last completed window, telling a tap from the
+    /// release of a hold, i.e. whether it reached `hold_ticks` before being released. Returns
+    /// `None` if the pin wasn't released. The release is cleared by this call and at the next
+    /// window, so it's only reported once
+    pub fn release_kind(&mut self) -> Option<ReleaseKind> {
+        self.release_kind.take()
+    }
+
+    /// Returns the number of `Repeat` states returned by `get_state` during the current hold, i.e.
+    /// the ordinal of the last repeat, for custom rate curves. It's reset on release
+    pub fn repeat_index(&self) -> u32 {
+        self.repeat_index
+    }
+
+    /// Returns whether the pin was released in the last completed window, i.e. once per release.
+    /// The flag is cleared by this call and at the next window
+    pub fn just_released(&mut self) -> bool {
+        core::mem::replace(&mut self.just_released, false)
+    }
+
+    /// Returns whether the pin reached the hold state since the last call, i.e. once per hold,
+    /// unlike `get_state` which keeps reporting `Hold`. The flag is cleared by this call and on
+    /// release
+    pub fn hold_edge(&mut self) -> bool {
+        let edge = self.hold_edge;
+        self.hold_edge = false;
+        edge
+    }
+
+    /// Same as `get_state`, but without consuming a pending `ChangedToPressed` or `Repeat`, so it
+    /// can be called any number of times, e.g. to render a UI twice per window
+    pub fn observe_state(&self) -> BtnState {
+        self.debounced_state
+    }
+
+    /// Returns whether the pin is currently pressed, according to the last completed window, in
+    /// any of the `ChangedToPressed`, `Pressed`, `Hold` or `Repeat` states. Unlike `get_state`,
+    /// this doesn't consume any state, e.g. pending repeats
+    pub fn level(&self) -> bool {
+        self.debounced_state != BtnState::UnPressed
+    }
+
+    /// Clears the hold and repeat counters while keeping the debounced state and configuration.
+    /// A pin already pressed stays `Pressed`, without a new `ChangedToPressed`, but must be held
+    /// again for the whole hold time to reach `Hold`, a pending `hold_edge` being dropped
+    pub fn clear_counters(&mut self) {
+        let pressed = self.press_ticks.max(self.min_press_ticks);
+        if self.counter >= pressed {
+            self.counter = pressed;
+            self.hold_duration = pressed;
+        }
+        if let BtnState::Hold | BtnState::Repeat = self.debounced_state {
+            self.debounced_state = BtnState::Pressed;
+            self.last_debounced_state = BtnState::Pressed;
+        }
+        self.hold_reached = false;
+        self.hold_edge = false;
+        self.repeat_ticks = self.initial_repeat_ticks;
+        self.repeating = false;
+    }
+
+    /// Warms up the debouncer with a known level, as if a whole window of `press_ticks` samples
+    /// at that level had just been fed, e.g. when switching from an undebounced reading of the
+    /// pin. A pressed pin is reported as `Pressed`, without a `ChangedToPressed`, and its hold
+    /// time counts from there. Any pending event, e.g. a `Repeat` or a release, is dropped
+    pub fn preload(&mut self, pressed: bool) {
+        let ticks = self.press_ticks.saturating_add(T::ONE);
+        self.current_index = T::ZERO;
+        self.warmed_up = true;
+        self.window_completed = false;
+        self.last_sample = pressed != self.active_low;
+        self.repeat_ticks = self.initial_repeat_ticks;
+        self.repeating = false;
+        self.repeat_ended = false;
+        self.hold_reached = false;
+        self.hold_edge = false;
+        self.just_released = false;
+        self.release_kind = None;
+        self.repeat_index = 0;
+        if pressed {
+            self.counter = ticks;
+            self.hold_duration = ticks;
+            self.low_ticks = T::ZERO;
+        } else {
+            self.counter = T::ZERO;
+            self.hold_duration = T::ZERO;
+            self.low_ticks = self.release_ticks;
+        }
+        self.debounced_state = if pressed && self.counter >= self.min_press_ticks {
+            BtnState::Pressed
+        } else {
+            BtnState::UnPressed
+        };
+        self.last_debounced_state = self.debounced_state;
+        self.previous_state = self.debounced_state;
+    }
+
+    /// Returns for how many consecutive ticks the pin has been high. The measurement stops at the
+    /// value set by `set_max_hold_ticks`, which defaults to the repeat threshold, and it isn't
+    /// affected by `Repeat` states
+    pub fn held_ticks(&self) -> T {
+        self.hold_duration
+    }
+
+    /// Returns how many more ticks the pin must stay high to reach the hold state, e.g. for a
+    /// countdown UI, zero once it's reached. `Hold` is reported at the end of the window where it
+    /// reaches zero
+    pub fn ticks_until_hold(&self) -> T {
+        self.hold_ticks.saturating_sub(self.counter)
+    }
+
+    /// Returns how many more ticks the pin must stay high to reach the next `Repeat`, same as
+    /// `ticks_until_hold`. While repeat is disabled, see `set_repeat_enabled`, the value stops at
+    /// the repeat interval
+    pub fn ticks_until_repeat(&self) -> T {
+        self.hold_ticks
+            .saturating_add(self.repeat_ticks)
+            .saturating_sub(self.counter)
+    }
+
+    /// Returns for how long the pin has been high, in milliseconds, i.e. `held_ticks` scaled by
+    /// the period at which the `update` method is called. The measurement stops at the value set
+    /// by `set_max_hold_ticks`, so it must be raised for long holds, e.g. to 1000 ticks to measure
+    /// 10 seconds at a 10ms tick. The result saturates at `u32::MAX`, about 49 days, instead of
+    /// wrapping
+    pub fn hold_ms(&self, tick_period_ms: u32) -> u32 {
+        ticks_to_ms(self.hold_duration, tick_period_ms)
+    }
+
+    /// Returns how far the pin is from the hold state to the first repeat, scaled from zero at the
+    /// hold threshold up to 255 at the repeat threshold, handy to drive an animation or a PWM duty
+    /// cycle. Returns zero before the hold state
+    pub fn hold_level(&self) -> u8 {
+        scale_level(
+            self.hold_duration.to_u64(),
+            self.hold_ticks.saturating_add(T::ONE).to_u64(),
+            self.initial_repeat_ticks.to_u64(),
+        )
+    }
+
+    /// Returns for how many ticks the pin was pressed in its last completed press, latched when
+    /// its release was debounced. It's measured as in `held_ticks`, so it has the same clamp.
+    /// Returns zero if the pin was never released
+    pub fn last_press_duration(&self) -> T {
+        self.last_press_duration
+    }
+
+    /// Returns whether the last call to `update` completed a window, same as its return value
+    pub fn window_complete(&self) -> bool {
+        self.window_completed
+    }
+
+    /// Returns the last value passed to `update`, before any debouncing
+    pub fn last_raw_sample(&self) -> bool {
+        self.last_sample
+    }
+
+    /// Returns whether at least one full window of `press_ticks` samples was completed. Before
+    /// that, the pin is reported as `UnPressed` regardless of its input
+    pub fn is_warmed_up(&self) -> bool {
+        self.warmed_up
+    }
+
+    /// Returns the number of ticks, i.e. calls to the `update` method, since the debounced state
+    /// last changed, either from released to pressed or the other way around
+    pub fn ticks_since_change(&self) -> T {
+        self.ticks_since_change
+    }
+
+    /// Returns the debouncing period in milliseconds, given the period at which the `update`
+    /// method is called
+    pub fn debounce_period_ms(&self, tick_period_ms: u32) -> u32 {
+        ticks_to_ms(self.press_ticks.saturating_add(T::ONE), tick_period_ms)
+    }
+
+    /// Returns the time in milliseconds the pin must be held to reach the hold state, given the
+    /// period at which the `update` method is called
+    pub fn hold_period_ms(&self, tick_period_ms: u32) -> u32 {
+        ticks_to_ms(self.hold_ticks.saturating_add(T::ONE), tick_period_ms)
+    }
+
+    /// Returns the time in milliseconds between the hold state and the first repeat, given the
+    /// period at which the `update` method is called
+    pub fn repeat_period_ms(&self, tick_period_ms: u32) -> u32 {
+        ticks_to_ms(self.initial_repeat_ticks, tick_period_ms)
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_must_use)]
+#[allow(deprecated)]
+mod tests {
+
+    use super::*;
+    use generic_array::typenum::consts::*;
+
+    #[test]
+    fn port_btn0_pressed() {
+        let presses: [u32; 8] = [0, 1, 0, 1, 1, 1, 1, 1];
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+
+        for &value in presses.iter().take(presses.len() / 2) {
+            port_debouncer.update(value);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+
+        for &value in presses.iter().skip(presses.len() / 2) {
+            port_debouncer.update(value);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+
+        let hold_presses = [1u32; 88];
+
+        for &value in hold_presses.iter() {
+            port_debouncer.update(value);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+
+        for &value in hold_presses.iter().take(8) {
+            port_debouncer.update(value);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+
+        let repeat_presses = [1u32; 20];
+
+        for &value in repeat_presses.iter() {
+            port_debouncer.update(value);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn port_btn1_pressed() {
+        let presses: [u32; 8] = [0, 1, 0, 1, 2, 2, 2, 2];
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+
+        for &value in presses.iter().take(presses.len() / 2) {
+            port_debouncer.update(value);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+
+        for &value in presses.iter().skip(presses.len() / 2) {
+            port_debouncer.update(value);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(1).unwrap()
+        );
+
+        let hold_presses = [2u32; 88];
+
+        for press in hold_presses.iter() {
+            port_debouncer.update(*press);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+
+        for &value in hold_presses.iter().take(8) {
+            port_debouncer.update(value);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(1).unwrap());
+
+        let repeat_presses = [2u32; 20];
+
+        for press in repeat_presses.iter() {
+            port_debouncer.update(*press);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(1).unwrap());
+
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(1).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn port_out_of_bound_btn() {
+        let presses: [u32; 8] = [1, 1, 1, 1, 2, 2, 2, 2];
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+
+        for &value in presses.iter() {
+            port_debouncer.update(value);
+        }
+        let _ = port_debouncer.get_state(1).unwrap();
+    }
+
+    #[test]
+    fn pin_pressed() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        let presses: [bool; 8] = [false, true, false, true, true, true, true, true];
+
+        for &value in presses.iter().take(presses.len() / 2) {
+            pin_debouncer.update(value);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+
+        for &value in presses.iter().skip(presses.len() / 2) {
+            pin_debouncer.update(value);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+
+        for _ in 0..88 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+
+        for _ in 0..8 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+
+        for _ in 0..20 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn port_min_press_filters_taps() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+        port_debouncer.set_min_press_ticks(12);
+
+        for _ in 0..8 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+
+        for _ in 0..12 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+
+        for _ in 0..4 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn pin_min_press_filters_taps() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        pin_debouncer.set_min_press_ticks(12);
+
+        for _ in 0..8 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+
+        for _ in 0..12 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn port_ticks_since_change() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+
+        for _ in 0..8 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(4, port_debouncer.ticks_since_change(0).unwrap());
+        assert_eq!(8, port_debouncer.ticks_since_change(1).unwrap());
+
+        for _ in 0..6 {
+            port_debouncer.update(0);
+        }
+        assert_eq!(2, port_debouncer.ticks_since_change(0).unwrap());
+        assert!(port_debouncer.ticks_since_change(2).is_err());
+    }
+
+    #[test]
+    fn pin_ticks_since_change() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        for _ in 0..10 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(6, pin_debouncer.ticks_since_change());
+
+        for _ in 0..8 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(2, pin_debouncer.ticks_since_change());
+    }
+
+    #[test]
+    fn port_repeat_acceleration() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+        port_debouncer.set_repeat_acceleration(40, 8, 16);
+
+        let mut repeats = [0usize; 4];
+        let mut count = 0;
+        for tick in 1..=400 {
+            if port_debouncer.update(1)
+                && port_debouncer.get_state(0).unwrap() == BtnState::Repeat
+                && count < repeats.len()
+            {
+                repeats[count] = tick;
+                count += 1;
+            }
+        }
+        assert_eq!([140, 164, 172, 180], repeats);
+
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+
+        for tick in 1..=140 {
+            if port_debouncer.update(1) && tick < 140 {
+                assert_ne!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+            }
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn pin_repeat_acceleration() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        pin_debouncer.set_repeat_acceleration(40, 8, 16);
+
+        let mut repeats = [0u32; 4];
+        let mut count = 0;
+        for tick in 1..=400 {
+            if pin_debouncer.update(true)
+                && pin_debouncer.get_state() == BtnState::Repeat
+                && count < repeats.len()
+            {
+                repeats[count] = tick;
+                count += 1;
+            }
+        }
+        assert_eq!([140, 164, 172, 180], repeats);
+    }
+
+    #[test]
+    fn port_normally_closed() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        port_debouncer.set_normally_closed(0b10);
+
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(1).unwrap()
+        );
+    }
+
+    #[test]
+    fn port_snapshot() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+
+        for _ in 0..8 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(
+            PortSnapshot {
+                debounced_state: 0b01,
+                changed_to_pressed: 0,
+                counters: GenericArray::clone_from_slice(&[8, 0]),
+            },
+            port_debouncer.snapshot()
+        );
+    }
+
+    #[test]
+    fn port_thresholds_changed_mid_hold() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+
+        for _ in 0..112 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+
+        port_debouncer.set_hold_ticks(40);
+        port_debouncer.set_repeat_ticks(8);
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+
+        port_debouncer.set_hold_ticks(200);
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn period_helpers() {
+        let port_debouncer: PortDebouncer<U8, U1> = PortDebouncer::new(20, 100);
+        assert_eq!(40, port_debouncer.debounce_period_ms(5));
+        assert_eq!(520, port_debouncer.hold_period_ms(5));
+        assert_eq!(100, port_debouncer.repeat_period_ms(5));
+
+        let pin_debouncer = PinDebouncer::new(8, 20, 100);
+        assert_eq!(40, pin_debouncer.debounce_period_ms(5));
+        assert_eq!(500, pin_debouncer.hold_period_ms(5));
+        assert_eq!(100, pin_debouncer.repeat_period_ms(5));
+    }
+
+    #[test]
+    fn warm_up() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        for _ in 0..3 {
+            port_debouncer.update(1);
+            pin_debouncer.update(true);
+        }
+        assert!(!port_debouncer.is_warmed_up());
+        assert!(!pin_debouncer.is_warmed_up());
+
+        port_debouncer.update(1);
+        pin_debouncer.update(true);
+        assert!(port_debouncer.is_warmed_up());
+        assert!(pin_debouncer.is_warmed_up());
+    }
+
+    #[test]
+    fn port_process_samples() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        let samples = [1, 3, 1, 3, 3, 3, 3, 3, 0, 0, 0, 0];
+
+        let mut transitions = [(0, 0, BtnState::UnPressed); 5];
+        let mut count = 0;
+        let states = port_debouncer.process_samples_with(&samples, |index, pin, state| {
+            transitions[count] = (index, pin, state);
+            count += 1;
+        });
+        assert_eq!(
+            [
+                (3, 0, BtnState::ChangedToPressed),
+                (7, 0, BtnState::Pressed),
+                (7, 1, BtnState::ChangedToPressed),
+                (11, 0, BtnState::UnPressed),
+                (11, 1, BtnState::UnPressed),
+            ],
+            transitions
+        );
+        assert_eq!(&[BtnState::UnPressed; 2], states.as_slice());
+
+        let states = port_debouncer.process_samples(&[2, 2, 2, 2, 2]);
+        assert_eq!(
+            &[BtnState::UnPressed, BtnState::ChangedToPressed],
+            states.as_slice()
+        );
+    }
+
+    #[test]
+    fn port_force_state() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+
+        port_debouncer.force_state(1, true).unwrap();
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+
+        port_debouncer.force_state(1, false).unwrap();
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(1).unwrap()
+        );
+        assert!(port_debouncer.force_state(2, true).is_err());
+    }
+
+    #[test]
+    fn port_enabled_mask() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        port_debouncer.set_enabled_mask(0b01);
+
+        for _ in 0..8 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+
+        port_debouncer.set_enabled_mask(0b11);
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+    }
+
+    #[test]
+    fn port_history() {
+        let mut port_debouncer: PortDebouncer<U4, U2, U2> = PortDebouncer::new(20, 12);
+
+        assert!(port_debouncer.history().is_empty());
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(
+            &[(4, 0, BtnState::ChangedToPressed)],
+            port_debouncer.history()
+        );
+
+        for _ in 0..8 {
+            port_debouncer.update(0b11);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        assert_eq!(
+            &[(16, 0, BtnState::UnPressed), (16, 1, BtnState::Hold)],
+            port_debouncer.history()
+        );
+
+        let mut no_history: PortDebouncer<U4, U2> = PortDebouncer::new(20, 12);
+        for _ in 0..4 {
+            no_history.update(0b01);
+        }
+        assert!(no_history.history().is_empty());
+    }
+
+    #[test]
+    fn port_priority_mode() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 4);
+
+        for _ in 0..4 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+
+        port_debouncer.set_priority_mode(PriorityMode::DurationFirst);
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn pin_priority_mode() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 4);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 4);
+        pin_debouncer.set_priority_mode(PriorityMode::DurationFirst);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn port_pressed_count() {
+        let mut port_debouncer: PortDebouncer<U4, U3> = PortDebouncer::new(20, 100);
+        assert!(!port_debouncer.any_pressed());
+
+        for _ in 0..4 {
+            port_debouncer.update(0b1101);
+        }
+        assert_eq!(2, port_debouncer.pressed_count());
+        assert!(port_debouncer.any_pressed());
+    }
+
+    #[test]
+    fn port_try_new() {
+        assert!(PortDebouncer::<U4, U32>::try_new(20, 100).is_ok());
+        assert!(PortDebouncer::<U4, U33>::try_new(20, 100).is_err());
+    }
+
+    #[test]
+    fn port_repeat_ended() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+
+        for _ in 0..100 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert!(!port_debouncer.repeat_ended(0).unwrap());
+
+        for _ in 0..124 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        assert!(!port_debouncer.repeat_ended(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert!(port_debouncer.repeat_ended(0).unwrap());
+        assert!(!port_debouncer.repeat_ended(0).unwrap());
+    }
+
+    #[test]
+    fn pin_repeat_ended() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        for _ in 0..120 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+        assert!(!pin_debouncer.repeat_ended());
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert!(pin_debouncer.repeat_ended());
+        assert!(!pin_debouncer.repeat_ended());
+    }
+
+    #[test]
+    fn port_small_word() {
+        let mut port_debouncer: PortDebouncer<U8, U8, U0, u8> = PortDebouncer::new(16, 96);
+
+        for _ in 0..8 {
+            port_debouncer.update(0x80);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(7).unwrap()
+        );
+        assert_eq!(1, port_debouncer.pressed_count());
+
+        assert!(
+            core::mem::size_of::<PortDebouncer<U8, U8, U0, u16>>()
+                < core::mem::size_of::<PortDebouncer<U8, U8>>()
+        );
+        assert!(PortDebouncer::<U8, U9, U0, u8>::try_new(16, 96).is_err());
+    }
+
+    #[test]
+    fn last_raw_sample() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        for &value in [3, 1, 2, 0, 2].iter() {
+            port_debouncer.update(value);
+            assert_eq!(value, port_debouncer.last_raw_sample());
+            pin_debouncer.update(value & 1 != 0);
+            assert_eq!(value & 1 != 0, pin_debouncer.last_raw_sample());
+        }
+    }
+
+    #[test]
+    fn port_pressed_keycodes() {
+        let mut port_debouncer: PortDebouncer<U4, U4> = PortDebouncer::new(20, 100);
+        let map = [0x04, 0x05, 0x06, 0x07];
+
+        for _ in 0..4 {
+            port_debouncer.update(0b1010);
+        }
+        let mut codes = port_debouncer.pressed_keycodes(&map);
+        assert_eq!(Some(0x05), codes.next());
+        assert_eq!(Some(0x07), codes.next());
+        assert_eq!(None, codes.next());
+    }
+
+    #[test]
+    fn port_max_hold_ticks() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+
+        for _ in 0..200 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(120, port_debouncer.held_ticks(0).unwrap());
+
+        port_debouncer.set_max_hold_ticks(1000);
+        for _ in 0..200 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(320, port_debouncer.held_ticks(0).unwrap());
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert_eq!(0, port_debouncer.held_ticks(0).unwrap());
+    }
+
+    #[test]
+    fn pin_max_hold_ticks() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        for _ in 0..200 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(120, pin_debouncer.held_ticks());
+
+        pin_debouncer.set_max_hold_ticks(1000);
+        for _ in 0..200 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(320, pin_debouncer.held_ticks());
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(0, pin_debouncer.held_ticks());
+    }
+
+    #[test]
+    fn error_code() {
+        assert_eq!(1, Error::BtnUninitialized.code());
+        assert_eq!(2, Error::TooManyButtons.code());
+        assert_eq!(3, Error::SequenceTooLong.code());
+        assert_eq!(4, Error::LengthMismatch.code());
+        assert_eq!(5, Error::BufferTooSmall.code());
+        assert_eq!(6, Error::ZeroTickPeriod.code());
+        assert_eq!(7, Error::InvalidWindow.code());
+    }
+
+    #[test]
+    fn window_complete() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        for _ in 0..3 {
+            assert!(!port_debouncer.update(1));
+            assert!(!port_debouncer.window_complete());
+            assert!(!pin_debouncer.update(true));
+            assert!(!pin_debouncer.window_complete());
+        }
+        assert!(port_debouncer.update(1));
+        assert!(port_debouncer.window_complete());
+        assert!(pin_debouncer.update(true));
+        assert!(pin_debouncer.window_complete());
+
+        port_debouncer.update(1);
+        assert!(!port_debouncer.window_complete());
+    }
+
+    #[test]
+    fn pin_repeat_after_release_mid_window() {
+        let mut pin_debouncer = PinDebouncer::new(4, 100, 5);
+
+        for _ in 0..108 {
+            pin_debouncer.update(true);
+        }
+        pin_debouncer.update(false);
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+
+        for _ in 0..3 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn port_stuck_button() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        port_debouncer.set_max_hold_ticks(2000);
+
+        for _ in 0..1000 {
+            port_debouncer.update(0b01);
+        }
+        assert!(!port_debouncer.is_stuck(0, 1000).unwrap());
+        port_debouncer.update(0b01);
+        assert!(!port_debouncer.is_stuck(0, 1000).unwrap());
+        for _ in 0..3 {
+            port_debouncer.update(0b01);
+        }
+        assert!(port_debouncer.is_stuck(0, 1000).unwrap());
+        assert!(!port_debouncer.is_stuck(1, 1000).unwrap());
+        assert!(port_debouncer.is_stuck(2, 1000).is_err());
+    }
+
+    #[test]
+    fn port_partial_window() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+
+        port_debouncer.update(0b11);
+        port_debouncer.update(0b01);
+        assert_eq!(0b01, port_debouncer.partial_window());
+        port_debouncer.update(0b01);
+        port_debouncer.update(0b01);
+        assert_eq!(0b01, port_debouncer.partial_window());
+
+        port_debouncer.update(0b10);
+        assert_eq!(0b10, port_debouncer.partial_window());
+    }
+
+    #[test]
+    fn port_take_changed() {
+        let mut port_debouncer: PortDebouncer<U2, U3> = PortDebouncer::new(20, 100);
+
+        for _ in 0..2 {
+            port_debouncer.update(0b001);
+        }
+        for _ in 0..2 {
+            port_debouncer.update(0b011);
+        }
+        for _ in 0..2 {
+            port_debouncer.update(0b010);
+        }
+        assert_eq!(0b011, port_debouncer.take_changed());
+        assert_eq!(0, port_debouncer.take_changed());
+
+        for _ in 0..2 {
+            port_debouncer.update(0b110);
+        }
+        assert_eq!(0b100, port_debouncer.take_changed());
+
+        // Disabled pins and presses too short to be reported are left out
+        port_debouncer.set_enabled_mask(0b011);
+        port_debouncer.set_min_press_ticks(4);
+        for _ in 0..2 {
+            port_debouncer.update(0b001);
+        }
+        assert_eq!(0b010, port_debouncer.take_changed());
+    }
+
+    #[test]
+    fn recommend_timings() {
+        assert_eq!(8, recommend_samples(40, 5));
+        assert_eq!(9, recommend_samples(41, 5));
+        assert_eq!(1, recommend_samples(0, 5));
+
+        assert_eq!(104, recommend_ticks(500, 5, 8));
+        assert_eq!(96, recommend_ticks(480, 5, 8));
+        assert_eq!(8, recommend_ticks(1, 5, 8));
+    }
+
+    #[test]
+    fn port_sample_history() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+
+        for sample in 1..=6 {
+            port_debouncer.update(sample);
+        }
+        let mut samples = port_debouncer.sample_history();
+        for expected in 3..=6 {
+            assert_eq!(Some(expected), samples.next());
+        }
+        assert_eq!(None, samples.next());
+    }
+
+    #[test]
+    fn port_confirm_windows() {
+        let mut port_debouncer: PortDebouncer<U2, U1> = PortDebouncer::new(20, 100);
+        port_debouncer.set_confirm_windows(2);
+
+        for _ in 0..4 {
+            port_debouncer.update(1);
+            port_debouncer.update(1);
+            assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+            port_debouncer.update(1);
+            port_debouncer.update(0);
+            assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        }
+
+        port_debouncer.update(1);
+        port_debouncer.update(1);
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        port_debouncer.update(1);
+        port_debouncer.update(1);
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+
+        port_debouncer.update(0);
+        port_debouncer.update(0);
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        port_debouncer.update(0);
+        port_debouncer.update(0);
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn last_press_duration() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+        for _ in 0..12 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(0, port_debouncer.last_press_duration(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0);
+        }
+        assert_eq!(12, port_debouncer.last_press_duration(0).unwrap());
+        assert!(port_debouncer.last_press_duration(1).is_err());
+
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        for _ in 0..10 {
+            pin_debouncer.update(true);
+        }
+        for _ in 0..5 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(0, pin_debouncer.last_press_duration());
+        pin_debouncer.update(false);
+        assert_eq!(10, pin_debouncer.last_press_duration());
+    }
+
+    #[test]
+    fn port_update_n_with() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 8);
+        assert!(!port_debouncer.update_n(1, 3));
+
+        let mut states = [BtnState::UnPressed; 4];
+        let mut windows = 0;
+        assert!(port_debouncer.update_n_with(1, 13, |debouncer| {
+            states[windows] = debouncer.get_state(0).unwrap();
+            windows += 1;
+        }));
+        assert_eq!(
+            [
+                BtnState::ChangedToPressed,
+                BtnState::Hold,
+                BtnState::Hold,
+                BtnState::Hold
+            ],
+            states
+        );
+        assert_eq!(4, windows);
+    }
+
+    #[test]
+    fn hold_level() {
+        let mut port_debouncer: PortDebouncer<U2, U1> = PortDebouncer::new(8, 4);
+        for _ in 0..2 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(0, port_debouncer.hold_level(0).unwrap());
+        for _ in 0..2 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(0, port_debouncer.hold_level(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(127, port_debouncer.hold_level(0).unwrap());
+        for _ in 0..8 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(255, port_debouncer.hold_level(0).unwrap());
+        assert!(port_debouncer.hold_level(1).is_err());
+
+        let mut pin_debouncer = PinDebouncer::new(2, 4, 4);
+        for _ in 0..6 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(127, pin_debouncer.hold_level());
+        pin_debouncer.update(false);
+        pin_debouncer.update(false);
+        assert_eq!(0, pin_debouncer.hold_level());
+    }
+
+    #[test]
+    fn from_config() {
+        let mut port_debouncer: PortDebouncer<U2, U2> = PortDebouncer::from_config(PortConfig {
+            normally_closed: 0b10,
+            enabled: 0b01,
+            ..Default::default()
+        });
+        for _ in 0..2 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(100, port_debouncer.hold_period_ms(1));
+
+        let pin_debouncer = PinDebouncer::from_config(PinConfig {
+            press_ticks: 2,
+            ..Default::default()
+        });
+        assert_eq!(2, pin_debouncer.debounce_period_ms(1));
+        assert_eq!(20, pin_debouncer.repeat_period_ms(1));
+    }
+
+    #[test]
+    fn port_non_multiple_thresholds() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(10, 18);
+
+        for _ in 0..19 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        port_debouncer.update(1);
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+
+        let mut repeats = 0;
+        for _ in 0..40 {
+            if port_debouncer.update(1) && port_debouncer.get_state(0).unwrap() == BtnState::Repeat
+            {
+                repeats += 1;
+            }
+        }
+        assert_eq!(4, repeats);
+    }
+
+    #[test]
+    fn clear_counters() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..100 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        port_debouncer.clear_counters();
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert!(!port_debouncer.hold_edge(0).unwrap());
+        for _ in 0..92 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        assert!(port_debouncer.hold_edge(0).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        for _ in 0..100 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+        pin_debouncer.clear_counters();
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+        assert!(!pin_debouncer.hold_edge());
+        for _ in 0..92 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+        assert!(pin_debouncer.hold_edge());
+
+        // A release before the hold time is reached again is a tap
+        pin_debouncer.clear_counters();
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        assert_eq!(Some(ReleaseKind::Tap), pin_debouncer.release_kind());
+    }
+
+    #[test]
+    fn port_pressed_in_mask() {
+        let mut port_debouncer: PortDebouncer<U2, U4> = PortDebouncer::new(20, 100);
+        for _ in 0..2 {
+            port_debouncer.update(0b0110);
+        }
+        assert!(port_debouncer.any_pressed_in(0b1100));
+        assert!(!port_debouncer.any_pressed_in(0b1001));
+        assert!(port_debouncer.all_pressed_in(0b0110));
+        assert!(!port_debouncer.all_pressed_in(0b0111));
+        assert!(port_debouncer.all_pressed_in(0));
+    }
+
+    #[test]
+    fn pin_release_ticks() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+
+        pin_debouncer.update(true);
+        pin_debouncer.update(false);
+        pin_debouncer.update(true);
+        pin_debouncer.update(true);
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+
+        pin_debouncer.set_release_ticks(1);
+        for _ in 0..6 {
+            pin_debouncer.update(true);
+        }
+        pin_debouncer.update(false);
+        pin_debouncer.update(true);
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn pin_release_ticks_bounce() {
+        // A bouncing contact never registers a press, however long the release countdown is
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        pin_debouncer.set_release_ticks(8);
+        for _ in 0..10 {
+            for &sample in [true, false, true, true].iter() {
+                pin_debouncer.update(sample);
+            }
+            assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        }
+    }
+
+    #[test]
+    fn pin_update_and_state() {
+        let mut pin_debouncer = PinDebouncer::new(4, 100, 100);
+        pin_debouncer.set_release_ticks(2);
+        for _ in 0..3 {
+            assert_eq!(BtnState::UnPressed, pin_debouncer.update_and_state(true));
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            pin_debouncer.update_and_state(true)
+        );
+        for _ in 0..4 {
+            assert_eq!(BtnState::Pressed, pin_debouncer.update_and_state(true));
+        }
+
+        // The release is reported before the end of the window
+        assert_eq!(BtnState::Pressed, pin_debouncer.update_and_state(false));
+        assert_eq!(BtnState::UnPressed, pin_debouncer.update_and_state(false));
+        for _ in 0..2 {
+            assert_eq!(BtnState::UnPressed, pin_debouncer.update_and_state(false));
+        }
+
+        // A press started mid-window is reported once the partial window reaches the threshold,
+        // instead of at the end of the window
+        for _ in 0..2 {
+            assert_eq!(BtnState::UnPressed, pin_debouncer.update_and_state(false));
+        }
+        for _ in 0..3 {
+            assert_eq!(BtnState::UnPressed, pin_debouncer.update_and_state(true));
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            pin_debouncer.update_and_state(true)
+        );
+        assert!(!pin_debouncer.window_complete());
+        assert_eq!(0, pin_debouncer.ticks_since_change());
+        for _ in 0..2 {
+            assert_eq!(BtnState::Pressed, pin_debouncer.update_and_state(true));
+        }
+        assert_eq!(BtnState::Pressed, pin_debouncer.observe_state());
+    }
+
+    #[test]
+    fn pin_active_low() {
+        let mut pin_debouncer = PinDebouncer::new_active_low(4, 20, 100);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+        assert!(!pin_debouncer.last_raw_sample());
+
+        pin_debouncer.preload(false);
+        assert!(pin_debouncer.last_raw_sample());
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+
+        let mut pin_debouncer: PinDebouncer<u16> = PinDebouncer::with_ticks_active_low(4, 20, 100);
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn pin_update_at() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        let start = u32::MAX - 1;
+        assert!(!pin_debouncer.update_at(true, start));
+        // Ticks u32::MAX and zero are filled with the previous sample
+        assert!(pin_debouncer.update_at(true, 1));
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+
+        // Released at tick two, the samples up to tick nine are missing
+        assert!(!pin_debouncer.update_at(false, 2));
+        assert!(pin_debouncer.update_at(false, 10));
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+
+        // A tick behind the previous one is ignored instead of replaying a wrapped around gap
+        let ticks_since_change = pin_debouncer.ticks_since_change();
+        assert!(!pin_debouncer.update_at(true, 5));
+        assert_eq!(ticks_since_change, pin_debouncer.ticks_since_change());
+    }
+
+    #[test]
+    fn pin_update_at_same_tick() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        for _ in 0..4 {
+            assert!(!pin_debouncer.update_at(true, 10));
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        assert!(pin_debouncer.update_at(true, 13));
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn pin_update_at_long_gap() {
+        // Only the ticks needed to saturate the counters are replayed, with the same outcome
+        for &gap in [100_001u32, 100_002, 100_003, 100_004].iter() {
+            let mut replayed: PinDebouncer<u64> = PinDebouncer::with_ticks(4, 20, 100);
+            replayed.set_max_hold_ticks(u64::MAX);
+            let mut fed: PinDebouncer<u64> = PinDebouncer::with_ticks(4, 20, 100);
+            fed.set_max_hold_ticks(u64::MAX);
+            replayed.update_at(true, 0);
+            replayed.update_at(true, gap);
+            fed.update(true);
+            let mut completed = false;
+            for _ in 0..gap {
+                completed |= fed.update(true);
+            }
+            assert!(completed);
+            assert_eq!(fed.observe_state(), replayed.observe_state());
+            assert_eq!(fed.held_ticks(), replayed.held_ticks());
+            assert_eq!(fed.ticks_since_change(), replayed.ticks_since_change());
+            for _ in 0..4 {
+                assert_eq!(fed.update(false), replayed.update(false));
+            }
+        }
+    }
+
+    #[test]
+    fn boundary_parameters_dont_panic() {
+        // Tests are built with overflow checks, so any wrapping arithmetic would panic here
+        let samples = [
+            true, true, false, true, true, true, false, false, false, true,
+        ];
+        for &(press, repeat, hold) in [
+            (0, 0, 0),
+            (1, 0, 0),
+            (1, 1, 1),
+            (1, u32::MAX, u32::MAX),
+            (2, u32::MAX, 0),
+        ]
+        .iter()
+        {
+            let mut pin_debouncer = PinDebouncer::new(press, repeat, hold);
+            pin_debouncer.set_repeat_acceleration(repeat, 0, u32::MAX);
+            for _ in 0..20 {
+                for &sample in samples.iter() {
+                    pin_debouncer.update(sample);
+                    pin_debouncer.get_state();
+                }
+            }
+            pin_debouncer.hold_level();
+            pin_debouncer.ticks_until_repeat();
+            pin_debouncer.hold_period_ms(u32::MAX);
+        }
+
+        for &(repeat, hold) in [
+            (0, 0),
+            (1, 1),
+            (usize::MAX, usize::MAX),
+            (u32::MAX as usize, 0),
+        ]
+        .iter()
+        {
+            let mut port_debouncer: PortDebouncer<U1, U2> = PortDebouncer::new(repeat, hold);
+            port_debouncer.set_repeat_acceleration(repeat, 0, usize::MAX);
+            for _ in 0..20 {
+                for &sample in samples.iter() {
+                    port_debouncer.update(sample as u32 | 0b10);
+                    port_debouncer.get_state(0).unwrap();
+                    port_debouncer.get_state(1).unwrap();
+                }
+            }
+            port_debouncer.update_n(0b11, 3);
+            port_debouncer.process_samples(&[0b01, 0b10, 0b11]);
+            port_debouncer.ticks_until_repeat(1).unwrap();
+            port_debouncer.hold_ticks_raw();
+            port_debouncer.hold_period_ms(u32::MAX);
+            port_debouncer.last_raw_sample();
+        }
+
+        // An empty window can't be debounced
+        assert!(matches!(
+            PortDebouncer::<U0, U2>::try_new(20, 100),
+            Err(Error::InvalidWindow)
+        ));
+    }
+
+    #[test]
+    fn pin_grace_ticks() {
+        let mut pin_debouncer = PinDebouncer::new(2, 100, 10);
+        pin_debouncer.set_grace_ticks(6);
+        for _ in 0..2 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+
+        // A re-press within the grace period continues the hold
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+        for _ in 0..8 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+
+        // Past the grace period it's released and the next press starts over
+        for _ in 0..10 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        for _ in 0..2 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+    }
+
+    #[test]
+    fn debug_format() {
+        use core::fmt::Write;
+
+        struct Sink(usize);
+        impl Write for Sink {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        let port_debouncer: PortDebouncer<U2, U1> = PortDebouncer::new(20, 100);
+        let pin_debouncer = PinDebouncer::new(4, 20, 100);
+        let mut sink = Sink(0);
+        write!(sink, "{:?} {:?}", port_debouncer, pin_debouncer).unwrap();
+        assert!(sink.0 > 0);
+    }
+
+    #[test]
+    fn port_more_buttons_than_bits() {
+        let mut port_debouncer: PortDebouncer<U2, U40> = PortDebouncer::new(20, 100);
+        for _ in 0..2 {
+            port_debouncer.update(u32::MAX);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(31).unwrap()
+        );
+        assert!(port_debouncer.get_state(32).is_err());
+        assert!(port_debouncer.get_state(39).is_err());
+        assert_eq!(32, port_debouncer.pressed_count());
+
+        let mut port_debouncer: PortDebouncer<U2, U40> = PortDebouncer::new(20, 100);
+        let states = port_debouncer.process_samples(&[u32::MAX; 4]);
+        assert_eq!(BtnState::Pressed, states[31]);
+        assert_eq!(BtnState::UnPressed, states[32]);
+        let states = port_debouncer.process_samples_with(&[0], |_, _, _| {});
+        assert_eq!(BtnState::Pressed, states[31]);
+        assert_eq!(BtnState::UnPressed, states[39]);
+    }
+
+    #[test]
+    fn port_changed_iter() {
+        let mut port_debouncer: PortDebouncer<U2, U4> = PortDebouncer::new(20, 100);
+        for _ in 0..2 {
+            port_debouncer.update(0b0011);
+        }
+        for _ in 0..2 {
+            port_debouncer.update(0b1010);
+        }
+        let mut changed = [(0, BtnState::Pressed); 2];
+        for (slot, pin_state) in changed.iter_mut().zip(port_debouncer.changed_iter()) {
+            *slot = pin_state;
+        }
+        assert_eq!(
+            [(0, BtnState::UnPressed), (3, BtnState::ChangedToPressed)],
+            changed
+        );
+        assert_eq!(2, port_debouncer.changed_iter().count());
+
+        for _ in 0..2 {
+            port_debouncer.update(0b1010);
+        }
+        assert_eq!(0, port_debouncer.changed_iter().count());
+    }
+
+    #[test]
+    fn pin_level() {
+        let mut pin_debouncer = PinDebouncer::new(4, 8, 8);
+        assert!(!pin_debouncer.level());
+        for _ in 0..16 {
+            pin_debouncer.update(true);
+        }
+        assert!(pin_debouncer.level());
+        assert!(pin_debouncer.level());
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert!(!pin_debouncer.level());
+    }
+
+    #[test]
+    fn port_assume_initial() {
+        let mut port_debouncer: PortDebouncer<U4, U3> = PortDebouncer::new(20, 100);
+        port_debouncer.assume_initial(0b011);
+        for _ in 0..4 {
+            port_debouncer.update(0b101);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(2).unwrap()
+        );
+        assert_eq!(0b110, port_debouncer.take_changed());
+
+        for _ in 0..92 {
+            port_debouncer.update(0b101);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0b101);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn hold_edge() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 12);
+        for _ in 0..8 {
+            port_debouncer.update(1);
+        }
+        assert!(!port_debouncer.hold_edge(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(1);
+        }
+        assert!(port_debouncer.hold_edge(0).unwrap());
+        assert!(!port_debouncer.hold_edge(0).unwrap());
+        for _ in 0..40 {
+            port_debouncer.update(1);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        assert!(!port_debouncer.hold_edge(0).unwrap());
+        assert!(port_debouncer.hold_edge(1).is_err());
+
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 12);
+        for _ in 0..8 {
+            pin_debouncer.update(true);
+        }
+        assert!(!pin_debouncer.hold_edge());
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert!(pin_debouncer.hold_edge());
+        assert!(!pin_debouncer.hold_edge());
+        for _ in 0..40 {
+            pin_debouncer.update(true);
+        }
+        assert!(!pin_debouncer.hold_edge());
+    }
+
+    #[test]
+    fn port_set_all_timings() {
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U0, U2> =
+            PortDebouncer::new(20, 100);
+        assert!(port_debouncer.set_all_timings(&[8, 16], &[4]).is_err());
+        port_debouncer.set_all_timings(&[8, 16], &[4, 8]).unwrap();
+
+        for _ in 0..8 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+        for _ in 0..8 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(1).unwrap());
+        for _ in 0..8 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(1).unwrap());
+
+        // Only the first pin has thresholds of its own, the other one keeps those of the port
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U0, U1> =
+            PortDebouncer::new(20, 100);
+        assert!(port_debouncer.set_all_timings(&[8, 16], &[4, 8]).is_err());
+        port_debouncer.set_all_timings(&[8], &[4]).unwrap();
+        for _ in 0..16 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(84, port_debouncer.ticks_until_hold(1).unwrap());
+
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        assert!(port_debouncer.set_all_timings(&[8], &[4]).is_err());
+    }
+
+    #[test]
+    fn repeat_interval() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(40, 20);
+        port_debouncer.set_repeat_interval_ticks(8);
+        let mut repeats = [0; 4];
+        let mut count = 0;
+        for tick in 1..=84 {
+            if port_debouncer.update(1) && port_debouncer.get_state(0).unwrap() == BtnState::Repeat
+            {
+                repeats[count] = tick;
+                count += 1;
+            }
+        }
+        assert_eq!([60, 68, 76, 84], repeats);
+
+        let mut pin_debouncer = PinDebouncer::new(4, 40, 20);
+        pin_debouncer.set_repeat_interval_ticks(8);
+        let mut repeats = [0; 4];
+        let mut count = 0;
+        for tick in 1..=84 {
+            if pin_debouncer.update(true) && pin_debouncer.get_state() == BtnState::Repeat {
+                repeats[count] = tick;
+                count += 1;
+            }
+        }
+        assert_eq!([60, 68, 76, 84], repeats);
+    }
+
+    #[test]
+    fn port_out_of_range_queries() {
+        let mut port_debouncer: PortDebouncer<U2, U10, U0, u8> = PortDebouncer::new(20, 100);
+        for pin in [8, 10, usize::MAX].iter().copied() {
+            assert!(port_debouncer.get_state(pin).is_err());
+            assert!(port_debouncer.repeat_ended(pin).is_err());
+            assert!(port_debouncer.hold_edge(pin).is_err());
+            assert!(port_debouncer.force_state(pin, true).is_err());
+            assert!(port_debouncer.held_ticks(pin).is_err());
+            assert!(port_debouncer.hold_level(pin).is_err());
+            assert!(port_debouncer.last_press_duration(pin).is_err());
+            assert!(port_debouncer.ticks_since_change(pin).is_err());
+        }
+        assert!(port_debouncer.get_state(7).is_ok());
+    }
+
+    #[test]
+    fn pin_tick() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        for _ in 0..3 {
+            assert_eq!(None, pin_debouncer.tick(true));
+        }
+        assert_eq!(Some(BtnState::ChangedToPressed), pin_debouncer.tick(true));
+        for _ in 0..3 {
+            assert_eq!(None, pin_debouncer.tick(true));
+        }
+        assert_eq!(Some(BtnState::Pressed), pin_debouncer.tick(true));
+    }
+
+    #[test]
+    fn observe_state() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(8, 8);
+        let mut pin_debouncer = PinDebouncer::new(4, 8, 8);
+        for _ in 0..16 {
+            port_debouncer.update(1);
+            pin_debouncer.update(true);
+        }
+        for _ in 0..2 {
+            assert_eq!(BtnState::Repeat, port_debouncer.observe_state(0).unwrap());
+            assert_eq!(BtnState::Repeat, pin_debouncer.observe_state());
+        }
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+        assert_eq!(BtnState::Hold, port_debouncer.observe_state(0).unwrap());
+        assert_eq!(BtnState::Hold, pin_debouncer.observe_state());
+        assert!(port_debouncer.observe_state(1).is_err());
+    }
+
+    #[test]
+    fn port_majority_mode() {
+        let samples = [1, 0, 1, 1, 1, 0, 1, 1];
+        let mut all_agree: PortDebouncer<U8, U1> = PortDebouncer::new(80, 160);
+        let mut majority: PortDebouncer<U8, U1> = PortDebouncer::new(80, 160);
+        majority.set_debounce_mode(DebounceMode::Majority(5));
+        for &sample in samples.iter() {
+            all_agree.update(sample);
+            majority.update(sample);
+        }
+        assert_eq!(BtnState::UnPressed, all_agree.get_state(0).unwrap());
+        assert_eq!(BtnState::ChangedToPressed, majority.get_state(0).unwrap());
+
+        for &sample in [1, 0, 0, 1, 0, 0, 1, 0].iter() {
+            majority.update(sample);
+        }
+        assert_eq!(BtnState::UnPressed, majority.get_state(0).unwrap());
+    }
+
+    #[test]
+    fn port_capacity() {
+        let port_debouncer: PortDebouncer<U8, U5> = PortDebouncer::new(20, 100);
+        assert_eq!(5, PortDebouncer::<U8, U5>::CAPACITY);
+        assert_eq!(5, port_debouncer.capacity());
+        assert_eq!(8, port_debouncer.sample_window());
+    }
+
+    #[test]
+    fn port_reset_pin() {
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U0, U0, U2> =
+            PortDebouncer::new(20, 100);
+        for _ in 0..8 {
+            port_debouncer.update(0b11);
+        }
+        port_debouncer.reset_pin(0).unwrap();
+        assert_eq!(
+            (BtnState::UnPressed, BtnState::UnPressed),
+            port_debouncer.transition(0).unwrap()
+        );
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(
+            (BtnState::UnPressed, BtnState::ChangedToPressed),
+            port_debouncer.transition(0).unwrap()
+        );
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+        assert!(port_debouncer.reset_pin(2).is_err());
+    }
+
+    #[test]
+    fn pin_transition() {
+        let mut pin_debouncer = PinDebouncer::new(4, 4, 8);
+        let mut transitions = [(BtnState::UnPressed, BtnState::UnPressed); 4];
+        for transition in transitions.iter_mut() {
+            for _ in 0..4 {
+                pin_debouncer.update(true);
+            }
+            *transition = pin_debouncer.transition();
+        }
+        assert_eq!(
+            [
+                (BtnState::UnPressed, BtnState::ChangedToPressed),
+                (BtnState::ChangedToPressed, BtnState::Hold),
+                (BtnState::Hold, BtnState::Repeat),
+                (BtnState::Repeat, BtnState::Repeat),
+            ],
+            transitions
+        );
+    }
+
+    #[test]
+    fn port_transition() {
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U0, U0, U2> =
+            PortDebouncer::new(4, 8);
+        let mut transitions = [(BtnState::UnPressed, BtnState::UnPressed); 4];
+        for transition in transitions.iter_mut() {
+            for _ in 0..4 {
+                port_debouncer.update(0b01);
+            }
+            *transition = port_debouncer.transition(0).unwrap();
+        }
+        assert_eq!(
+            [
+                (BtnState::UnPressed, BtnState::ChangedToPressed),
+                (BtnState::ChangedToPressed, BtnState::Hold),
+                (BtnState::Hold, BtnState::Repeat),
+                (BtnState::Repeat, BtnState::Repeat),
+            ],
+            transitions
+        );
+        assert_eq!(
+            (BtnState::UnPressed, BtnState::UnPressed),
+            port_debouncer.transition(1).unwrap()
+        );
+
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U0, U0, U1> =
+            PortDebouncer::new(4, 8);
+        assert!(port_debouncer.transition(0).is_ok());
+        assert!(port_debouncer.transition(1).is_err());
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(4, 8);
+        assert!(port_debouncer.transition(0).is_err());
+    }
+
+    #[test]
+    fn port_toggled() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..8 {
+            port_debouncer.update(0b01);
+        }
+        assert!(port_debouncer.toggled(0).unwrap());
+        assert!(!port_debouncer.toggled(1).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0b00);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(0b10, port_debouncer.toggled_mask());
+
+        port_debouncer.set_toggled(0, true).unwrap();
+        assert_eq!(0b11, port_debouncer.toggled_mask());
+        assert!(port_debouncer.toggled(2).is_err());
+    }
+
+    #[test]
+    fn port_states_iter() {
+        let mut port_debouncer: PortDebouncer<U4, U3> = PortDebouncer::new(20, 100);
+        for _ in 0..4 {
+            port_debouncer.update(0b101);
+        }
+        let mut states = [(0, BtnState::UnPressed); 3];
+        for (slot, entry) in states.iter_mut().zip(&port_debouncer) {
+            *slot = entry;
+        }
+        assert_eq!(
+            [
+                (0, BtnState::ChangedToPressed),
+                (1, BtnState::UnPressed),
+                (2, BtnState::ChangedToPressed),
+            ],
+            states
+        );
+        assert_eq!(
+            2,
+            port_debouncer
+                .states()
+                .filter(|&(_, state)| state != BtnState::UnPressed)
+                .count()
+        );
+
+        let narrow: PortDebouncer<U4, U10, U0, u8> = PortDebouncer::new(20, 100);
+        assert_eq!(8, narrow.states().count());
+    }
+
+    #[test]
+    fn port_pin_window() {
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U2> =
+            PortDebouncer::new(20, 100);
+        port_debouncer.set_pin_window(0, 2).unwrap();
+        port_debouncer.update(0b00);
+        port_debouncer.update(0b11);
+        port_debouncer.update(0b10);
+        port_debouncer.update(0b11);
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+
+        port_debouncer.update(0b00);
+        port_debouncer.update(0b10);
+        port_debouncer.update(0b11);
+        port_debouncer.update(0b11);
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+
+        port_debouncer.set_pin_window(1, 0).unwrap();
+        port_debouncer.set_debounce_mode(DebounceMode::Majority(1));
+        for _ in 0..3 {
+            port_debouncer.update(0b10);
+        }
+        port_debouncer.update(0b00);
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        assert!(port_debouncer.set_pin_window(2, 1).is_err());
+
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u32, U0, u32, U1> =
+            PortDebouncer::new(20, 100);
+        assert!(port_debouncer.set_pin_window(0, 2).is_ok());
+        assert!(port_debouncer.set_pin_window(1, 2).is_err());
+    }
+
+    #[test]
+    fn pin_just_released() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert!(!pin_debouncer.just_released());
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert!(pin_debouncer.just_released());
+        assert!(!pin_debouncer.just_released());
+
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        for _ in 0..8 {
+            pin_debouncer.update(false);
+        }
+        assert!(!pin_debouncer.just_released());
+    }
+
+    #[test]
+    fn port_just_released() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        assert!(port_debouncer.just_released(0).unwrap());
+        assert!(!port_debouncer.just_released(0).unwrap());
+        assert!(!port_debouncer.just_released(1).unwrap());
+
+        for _ in 0..8 {
+            port_debouncer.update(0b00);
+        }
+        assert!(!port_debouncer.just_released(1).unwrap());
+        assert!(port_debouncer.just_released(2).is_err());
+    }
+
+    #[test]
+    fn btn_state_from_u8() {
+        for state in [
+            BtnState::Pressed,
+            BtnState::UnPressed,
+            BtnState::Repeat,
+            BtnState::Hold,
+            BtnState::ChangedToPressed,
+            BtnState::FirmPressed,
+        ]
+        .iter()
+        {
+            assert_eq!(Some(*state), BtnState::from_u8(*state as u8));
+        }
+        assert_eq!(4, BtnState::ChangedToPressed as u8);
+        assert_eq!(None, BtnState::from_u8(6));
+    }
+
+    #[test]
+    fn port_update_pins() {
+        let mut port_debouncer: PortDebouncer<U4, U3> = PortDebouncer::new(20, 100);
+        port_debouncer.update_pins(&[(0, true), (2, true)]);
+        port_debouncer.update_pins(&[(2, false)]);
+        port_debouncer.update_pins(&[(1, true), (40, true)]);
+        assert!(port_debouncer.update_pins(&[]));
+        assert_eq!(0b011, port_debouncer.last_raw_sample());
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(2).unwrap());
+    }
+
+    #[test]
+    fn port_bounce_count() {
+        let mut port_debouncer: PortDebouncer<U4, U3, U0, u32, U2> = PortDebouncer::new(20, 100);
+        for &sample in [0b000, 0b111, 0b000, 0b111, 0b111, 0b110, 0b111, 0b111].iter() {
+            port_debouncer.update(sample);
+        }
+        assert_eq!(5, port_debouncer.bounce_count(0).unwrap());
+        assert_eq!(3, port_debouncer.bounce_count(1).unwrap());
+        assert_eq!(0, port_debouncer.bounce_count(2).unwrap());
+        assert!(port_debouncer.bounce_count(3).is_err());
+
+        port_debouncer.reset_bounce_counts();
+        assert_eq!(0, port_debouncer.bounce_count(0).unwrap());
+    }
+
+    #[test]
+    fn pin_repeat_index() {
+        let mut pin_debouncer = PinDebouncer::new(4, 4, 8);
+        let mut indexes = [0; 4];
+        for index in indexes.iter_mut() {
+            for _ in 0..4 {
+                pin_debouncer.update(true);
+            }
+            pin_debouncer.get_state();
+            *index = pin_debouncer.repeat_index();
+        }
+        assert_eq!([0, 0, 1, 2], indexes);
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(0, pin_debouncer.repeat_index());
+    }
+
+    #[test]
+    fn port_repeat_index() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(4, 8);
+        let mut indexes = [0; 4];
+        for index in indexes.iter_mut() {
+            for _ in 0..4 {
+                port_debouncer.update(0b01);
+            }
+            port_debouncer.get_state(0).unwrap();
+            *index = port_debouncer.repeat_index(0).unwrap();
+        }
+        assert_eq!([0, 0, 1, 2], indexes);
+
+        for _ in 0..4 {
+            port_debouncer.update(0b00);
+        }
+        assert_eq!(0, port_debouncer.repeat_index(0).unwrap());
+        assert!(port_debouncer.repeat_index(2).is_err());
+    }
+
+    #[test]
+    fn port_shifted_state() {
+        let mut bank_a: PortDebouncer<U4, U3, U0, u8> = PortDebouncer::new(20, 100);
+        let mut bank_b: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..4 {
+            bank_a.update(0b101);
+            bank_b.update(0b10);
+        }
+        assert_eq!(0b10101, bank_a.shifted_state(0) | bank_b.shifted_state(3));
+        assert_eq!(0b10000, bank_b.shifted_changed(3));
+        assert_eq!(1 << 31, bank_b.shifted_state(30));
+
+        for _ in 0..4 {
+            bank_b.update(0b00);
+        }
+        assert_eq!(0, bank_b.shifted_state(3));
+        assert_eq!(0b10000, bank_b.shifted_changed(3));
+    }
+
+    #[test]
+    fn pin_hold_ms() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        pin_debouncer.set_max_hold_ticks(1000);
+        for _ in 0..1200 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(10_000, pin_debouncer.hold_ms(10));
+        assert_eq!(u32::MAX, pin_debouncer.hold_ms(u32::MAX));
+
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(0, pin_debouncer.hold_ms(10));
+    }
+
+    #[test]
+    fn port_lockout_group() {
+        let mut port_debouncer: PortDebouncer<U4, U4> = PortDebouncer::new(20, 100);
+        port_debouncer.lockout_group(0b0111);
+        for _ in 0..4 {
+            port_debouncer.update(0b0100);
+        }
+        assert_eq!(Some(2), port_debouncer.lockout_winner());
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(2).unwrap()
+        );
+
+        for _ in 0..4 {
+            port_debouncer.update(0b1111);
+        }
+        assert_eq!(Some(2), port_debouncer.lockout_winner());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(2).unwrap());
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(3).unwrap()
+        );
+
+        port_debouncer.clear_lockout();
+        for _ in 0..4 {
+            port_debouncer.update(0b0000);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b0011);
+        }
+        assert_eq!(Some(0), port_debouncer.lockout_winner());
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+    }
+
+    #[test]
+    fn pin_release_kind() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 8);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(Some(ReleaseKind::Tap), pin_debouncer.release_kind());
+        assert_eq!(None, pin_debouncer.release_kind());
+
+        for _ in 0..12 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(None, pin_debouncer.release_kind());
+        for _ in 0..4 {
+            pin_debouncer.update(false);
+        }
+        assert_eq!(Some(ReleaseKind::LongHold), pin_debouncer.release_kind());
+    }
+
+    #[test]
+    fn port_release_kind() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 8);
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b00);
+        }
+        assert_eq!(None, port_debouncer.release_kind(0).unwrap());
+        assert_eq!(
+            Some(ReleaseKind::LongHold),
+            port_debouncer.release_kind(1).unwrap()
+        );
+        assert_eq!(None, port_debouncer.release_kind(1).unwrap());
+
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b00);
+        }
+        assert_eq!(
+            Some(ReleaseKind::Tap),
+            port_debouncer.release_kind(0).unwrap()
+        );
+        assert!(port_debouncer.release_kind(2).is_err());
+    }
+
+    #[test]
+    fn port_release_samples() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        port_debouncer.set_release_samples(2);
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+
+        // A bounce and a single released sample at the end of the window keep the press
+        for &sample in [0b10, 0b11, 0b11, 0b10].iter() {
+            port_debouncer.update(sample);
         }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
 
-        self.current_index = 0;
-        if self.counter >= self.press_ticks {
-            self.debounced_state = BtnState::Pressed;
-        } else {
-            self.debounced_state = BtnState::UnPressed;
+        // Released for good since the last sample of the previous window
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
         }
-        if (self.last_debounced_state == BtnState::UnPressed)
-            && (self.debounced_state == BtnState::Pressed)
-        {
-            self.debounced_state = BtnState::ChangedToPressed;
-        } else if self.counter >= self.hold_ticks + self.repeat_ticks {
-            self.debounced_state = BtnState::Repeat;
-        } else if self.counter >= self.hold_ticks {
-            self.debounced_state = BtnState::Hold;
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+
+        // Bounces while released don't press it again
+        for &sample in [0b11, 0b10, 0b11, 0b11].iter() {
+            port_debouncer.update(sample);
         }
-        self.last_debounced_state = self.debounced_state;
-        true
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
     }
 
-    pub fn get_state(&mut self) -> BtnState {
-        match self.debounced_state {
-            BtnState::Repeat => {
-                self.counter -= self.repeat_ticks;
-                self.debounced_state = BtnState::Hold;
-                BtnState::Repeat
-            }
-            other => other,
+    #[test]
+    fn port_update_detailed() {
+        let mut port_debouncer: PortDebouncer<U2, U2> = PortDebouncer::new(20, 100);
+        let mut windows = 0;
+        assert!(!port_debouncer.update_detailed(0b11, |_| windows += 1));
+        assert!(port_debouncer.update_detailed(0b01, |info| {
+            windows += 1;
+            assert_eq!(0b01, info.raw_and);
+            assert_eq!(0b01, info.debounced_state);
+            assert_eq!(0b00, info.last_debounced_state);
+            assert_eq!(0b01, info.changed_to_pressed);
+            assert_eq!(0b00, info.changed_to_unpressed);
+        }));
+        port_debouncer.update_detailed(0b10, |_| windows += 1);
+        port_debouncer.update_detailed(0b10, |info| {
+            windows += 1;
+            assert_eq!(0b10, info.raw_and);
+            assert_eq!(0b01, info.last_debounced_state);
+            assert_eq!(0b10, info.changed_to_pressed);
+            assert_eq!(0b01, info.changed_to_unpressed);
+        });
+        assert_eq!(2, windows);
+    }
+
+    #[test]
+    fn port_effective_thresholds() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(10, 18);
+        assert_eq!(5, port_debouncer.effective_hold_ticks());
+        assert_eq!(3, port_debouncer.effective_repeat_ticks());
+        assert_eq!(20, port_debouncer.hold_ticks_raw());
+
+        for _ in 0..16 {
+            port_debouncer.update(0b1);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.observe_state(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
         }
+        assert_eq!(BtnState::Hold, port_debouncer.observe_state(0).unwrap());
     }
-}
 
-#[cfg(test)]
-mod tests {
+    #[test]
+    fn port_simultaneous_presses() {
+        let mut port_debouncer: PortDebouncer<U4, U3> = PortDebouncer::new(20, 100);
+        // Pins zero and one pressed within the same window, pin two one window later
+        for &sample in [0b000, 0b001, 0b011, 0b011].iter() {
+            port_debouncer.update(sample);
+        }
+        for _ in 0..4 {
+            port_debouncer.update(0b011);
+        }
+        assert_eq!(0b011, port_debouncer.simultaneous_presses());
+        assert!(port_debouncer.pressed_together(0, 1).unwrap());
 
-    use super::*;
-    use generic_array::typenum::consts::*;
+        for _ in 0..4 {
+            port_debouncer.update(0b111);
+        }
+        assert_eq!(0b100, port_debouncer.simultaneous_presses());
+        assert!(!port_debouncer.pressed_together(1, 2).unwrap());
+        assert!(port_debouncer.pressed_together(0, 3).is_err());
+    }
 
     #[test]
-    fn port_btn0_pressed() {
-        let presses: [u32; 8] = [0, 1, 0, 1, 1, 1, 1, 1];
-        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
+    fn repeat_disabled() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(8, 8);
+        let mut pin_debouncer = PinDebouncer::new(4, 8, 8);
+        port_debouncer.set_repeat_enabled(false);
+        pin_debouncer.set_repeat_enabled(false);
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
+            pin_debouncer.update(true);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+        for _ in 0..100 {
+            if port_debouncer.update(0b1) {
+                assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+            }
+            if pin_debouncer.update(true) {
+                assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+            }
+        }
 
-        for &value in presses.iter().take(presses.len() / 2) {
-            port_debouncer.update(value);
+        // Back to repeating on the same hold
+        port_debouncer.set_repeat_enabled(true);
+        pin_debouncer.set_repeat_enabled(true);
+        for _ in 0..12 {
+            port_debouncer.update(0b1);
+            pin_debouncer.update(true);
         }
-        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
+    }
 
-        for &value in presses.iter().skip(presses.len() / 2) {
-            port_debouncer.update(value);
+    #[test]
+    fn repeat_ticks_zero() {
+        // A zero repeat interval disables the repeat state instead of repeating at every read
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(0, 8);
+        let mut pin_debouncer = PinDebouncer::new(4, 0, 8);
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
+            pin_debouncer.update(true);
         }
         assert_eq!(
             BtnState::ChangedToPressed,
             port_debouncer.get_state(0).unwrap()
         );
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+        for _ in 0..100 {
+            port_debouncer.update(0b1);
+            pin_debouncer.update(true);
+        }
+        for _ in 0..3 {
+            assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+            assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+        }
+    }
 
-        let hold_presses = [1u32; 88];
+    #[test]
+    fn ticks_until_hold_and_repeat() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(8, 8);
+        let mut pin_debouncer = PinDebouncer::new(4, 8, 8);
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
+            pin_debouncer.update(true);
+        }
+        assert_eq!(4, port_debouncer.ticks_until_hold(0).unwrap());
+        assert_eq!(12, port_debouncer.ticks_until_repeat(0).unwrap());
+        assert_eq!(3, pin_debouncer.ticks_until_hold());
+        assert_eq!(11, pin_debouncer.ticks_until_repeat());
+        assert!(port_debouncer.ticks_until_hold(1).is_err());
 
-        for &value in hold_presses.iter() {
-            port_debouncer.update(value);
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
+            pin_debouncer.update(true);
         }
-        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(0, port_debouncer.ticks_until_hold(0).unwrap());
+        assert_eq!(8, port_debouncer.ticks_until_repeat(0).unwrap());
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        assert_eq!(0, pin_debouncer.ticks_until_hold());
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+    }
 
-        for &value in hold_presses.iter().take(8) {
-            port_debouncer.update(value);
+    #[test]
+    fn port_changed_this_window() {
+        let mut port_debouncer: PortDebouncer<U2, U2> = PortDebouncer::new(20, 100);
+        port_debouncer.update(0b01);
+        assert!(!port_debouncer.changed_this_window());
+        port_debouncer.update(0b01);
+        assert!(port_debouncer.changed_this_window());
+        port_debouncer.update(0b01);
+        assert!(!port_debouncer.changed_this_window());
+        port_debouncer.update(0b01);
+        assert!(!port_debouncer.changed_this_window());
+
+        port_debouncer.update_n(0b00, 2);
+        assert!(port_debouncer.changed_this_window());
+        port_debouncer.set_enabled_mask(0b01);
+        port_debouncer.update_n(0b10, 2);
+        assert!(!port_debouncer.changed_this_window());
+    }
+
+    #[test]
+    fn port_first_pressed() {
+        let mut port_debouncer: PortDebouncer<U2, U4> = PortDebouncer::new(20, 100);
+        assert_eq!(None, port_debouncer.first_pressed());
+        assert_eq!(None, port_debouncer.first_changed_to_pressed());
+
+        port_debouncer.update_n(0b1100, 2);
+        assert_eq!(Some(2), port_debouncer.first_pressed());
+        assert_eq!(Some(2), port_debouncer.first_changed_to_pressed());
+
+        port_debouncer.update_n(0b1110, 2);
+        assert_eq!(Some(1), port_debouncer.first_pressed());
+        assert_eq!(Some(1), port_debouncer.first_changed_to_pressed());
+
+        port_debouncer.update_n(0b1110, 2);
+        assert_eq!(Some(1), port_debouncer.first_pressed());
+        assert_eq!(None, port_debouncer.first_changed_to_pressed());
+
+        // Bits past the initialized buttons are ignored
+        port_debouncer.update_n(0b1_0000, 2);
+        assert_eq!(None, port_debouncer.first_pressed());
+
+        // Disabled buttons are reported as released by all the queries
+        port_debouncer.update_n(0b0110, 2);
+        port_debouncer.set_enabled_mask(0b1011);
+        assert_eq!(Some(1), port_debouncer.first_pressed());
+        assert_eq!(1, port_debouncer.pressed_count());
+        assert!(!port_debouncer.any_pressed_in(0b0100));
+        assert!(!port_debouncer.all_pressed_in(0b0110));
+        assert!(port_debouncer.all_pressed_in(0b0010));
+        assert!(port_debouncer
+            .pressed_keycodes(&[4, 5, 6, 7])
+            .eq([5].iter().copied()));
+        port_debouncer.set_enabled_mask(0b1001);
+        assert!(!port_debouncer.any_pressed());
+        assert_eq!(None, port_debouncer.first_pressed());
+    }
+
+    #[test]
+    fn port_ticks_types() {
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u8, U0, u64> = PortDebouncer::new(20, 8);
+        port_debouncer.set_max_hold_ticks(usize::MAX);
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
         }
         assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        assert_eq!(8u64, port_debouncer.held_ticks(0).unwrap());
 
-        let repeat_presses = [1u32; 20];
+        // The counters saturate at the maximum of the type
+        let mut port_debouncer: PortDebouncer<U4, U2, U0, u8, U0, u8> = PortDebouncer::new(20, 8);
+        port_debouncer.set_max_hold_ticks(usize::MAX);
+        for _ in 0..300 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(u8::MAX, port_debouncer.held_ticks(0).unwrap());
+        assert_eq!(0, port_debouncer.held_ticks(1).unwrap());
+    }
 
-        for &value in repeat_presses.iter() {
-            port_debouncer.update(value);
+    #[test]
+    fn port_restore_counters() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 12);
+        for _ in 0..8 {
+            port_debouncer.update(0b01);
         }
-        assert_eq!(BtnState::Repeat, port_debouncer.get_state(0).unwrap());
+        let counters = *port_debouncer.counters();
+        let debounced_state = port_debouncer.debounced_state();
+        let last_debounced_state = port_debouncer.last_debounced_state();
+        assert_eq!(0b01, debounced_state);
+        assert_eq!(8, counters[0]);
 
-        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
+        let mut restored: PortDebouncer<U4, U2> = PortDebouncer::new(20, 12);
+        restored.restore_counters(&counters);
+        restored.set_debounced_state(debounced_state);
+        restored.set_last_debounced_state(last_debounced_state);
+        assert_eq!(&counters, restored.counters());
+        assert_eq!(BtnState::Pressed, restored.get_state(0).unwrap());
 
+        // The press resumes without a new edge and reaches the hold state on time
         for _ in 0..4 {
-            port_debouncer.update(0);
+            restored.update(0b01);
         }
-        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Hold, restored.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, restored.get_state(1).unwrap());
     }
 
     #[test]
-    fn port_btn1_pressed() {
-        let presses: [u32; 8] = [0, 1, 0, 1, 2, 2, 2, 2];
+    fn port_sticky_press() {
         let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        port_debouncer.set_sticky_press(true);
+        port_debouncer.update_n(0b01, 4);
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
 
-        for &value in presses.iter().take(presses.len() / 2) {
-            port_debouncer.update(value);
-        }
+        // The press stays latched across windows, even once released
+        port_debouncer.update_n(0b00, 8);
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
         assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
 
-        for &value in presses.iter().skip(presses.len() / 2) {
-            port_debouncer.update(value);
+        port_debouncer.ack_pressed(0).unwrap();
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        port_debouncer.update_n(0b01, 4);
+        port_debouncer.ack_pressed(0).unwrap();
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert!(port_debouncer.ack_pressed(2).is_err());
+    }
+
+    #[test]
+    fn port_grace_ticks() {
+        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(100, 20);
+        port_debouncer.set_grace_ticks(4);
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
         }
         assert_eq!(
             BtnState::ChangedToPressed,
-            port_debouncer.get_state(1).unwrap()
+            port_debouncer.get_state(0).unwrap()
         );
 
-        let hold_presses = [2u32; 88];
-
-        for press in hold_presses.iter() {
-            port_debouncer.update(*press);
+        // A re-press within the grace period continues the hold
+        for _ in 0..4 {
+            port_debouncer.update(0b0);
         }
-        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        for _ in 0..16 {
+            port_debouncer.update(0b1);
+        }
+        assert_eq!(BtnState::Hold, port_debouncer.get_state(0).unwrap());
 
-        for &value in hold_presses.iter().take(8) {
-            port_debouncer.update(value);
+        // Past the grace period it's released and the next press starts over
+        for _ in 0..8 {
+            port_debouncer.update(0b0);
         }
-        assert_eq!(BtnState::Hold, port_debouncer.get_state(1).unwrap());
+        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(0).unwrap());
+        for _ in 0..4 {
+            port_debouncer.update(0b1);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+    }
 
-        let repeat_presses = [2u32; 20];
+    #[test]
+    fn port_classify() {
+        #[derive(PartialEq, Debug)]
+        enum Gesture {
+            Idle,
+            Short,
+            Long,
+        }
 
-        for press in repeat_presses.iter() {
-            port_debouncer.update(*press);
+        fn gesture(ticks: u32, _changed: bool) -> Gesture {
+            match ticks {
+                0 => Gesture::Idle,
+                1..=7 => Gesture::Short,
+                _ => Gesture::Long,
+            }
         }
-        assert_eq!(BtnState::Repeat, port_debouncer.get_state(1).unwrap());
 
-        assert_eq!(BtnState::Hold, port_debouncer.get_state(1).unwrap());
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..4 {
+            port_debouncer.update(0b01);
+        }
+        assert_eq!(Gesture::Short, port_debouncer.classify(0, gesture).unwrap());
+        assert_eq!(Gesture::Idle, port_debouncer.classify(1, gesture).unwrap());
+        assert!(port_debouncer.classify(0, |_, changed| changed).unwrap());
 
         for _ in 0..4 {
-            port_debouncer.update(1);
+            port_debouncer.update(0b01);
         }
-        assert_eq!(BtnState::UnPressed, port_debouncer.get_state(1).unwrap());
+        assert_eq!(Gesture::Long, port_debouncer.classify(0, gesture).unwrap());
+        assert!(!port_debouncer.classify(0, |_, changed| changed).unwrap());
+        assert!(port_debouncer.classify(2, gesture).is_err());
     }
 
     #[test]
-    #[should_panic]
-    fn port_out_of_bound_btn() {
-        let presses: [u32; 8] = [1, 1, 1, 1, 2, 2, 2, 2];
-        let mut port_debouncer: PortDebouncer<U4, U1> = PortDebouncer::new(20, 100);
-
-        for &value in presses.iter() {
-            port_debouncer.update(value);
+    fn port_press_counts() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..3 {
+            for _ in 0..8 {
+                port_debouncer.update(0b01);
+            }
+            for _ in 0..4 {
+                port_debouncer.update(0b10);
+            }
         }
-        let _ = port_debouncer.get_state(1).unwrap();
+        assert_eq!(6, port_debouncer.total_presses());
+        assert_eq!(3, port_debouncer.presses(0).unwrap());
+        assert_eq!(3, port_debouncer.presses(1).unwrap());
+        assert!(port_debouncer.presses(2).is_err());
+
+        port_debouncer.reset_counts();
+        assert_eq!(0, port_debouncer.total_presses());
+        assert_eq!(0, port_debouncer.presses(1).unwrap());
     }
 
     #[test]
-    fn pin_pressed() {
+    fn pin_changed_to_pressed_read_once() {
         let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
-        let presses: [bool; 8] = [false, true, false, true, true, true, true, true];
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.observe_state());
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+        assert_eq!(BtnState::Pressed, pin_debouncer.observe_state());
 
-        for &value in presses.iter().take(presses.len() / 2) {
-            pin_debouncer.update(value);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
         }
-        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+    }
 
-        for &value in presses.iter().skip(presses.len() / 2) {
-            pin_debouncer.update(value);
+    #[test]
+    fn port_changed_to_pressed_read_once() {
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.observe_state(0).unwrap()
+        );
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.observe_state(0).unwrap());
+
+        // An unread edge is dropped at the next window
+        for _ in 0..4 {
+            port_debouncer.update(0b11);
+        }
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(0).unwrap());
+        assert_eq!(BtnState::Pressed, port_debouncer.get_state(1).unwrap());
+    }
+
+    #[test]
+    fn pin_ticks_types() {
+        let mut pin_debouncer: PinDebouncer<u64> = PinDebouncer::with_ticks(4, 20, 8);
+        pin_debouncer.set_max_hold_ticks(u64::from(u32::MAX) + 100);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
         }
         assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+        }
+        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+        assert_eq!(8u64, pin_debouncer.held_ticks());
 
-        for _ in 0..88 {
+        // The counters saturate at the maximum of the type
+        let mut pin_debouncer: PinDebouncer<u8> = PinDebouncer::with_ticks(4, 20, 8);
+        pin_debouncer.set_max_hold_ticks(u8::MAX);
+        for _ in 0..300 {
             pin_debouncer.update(true);
         }
+        assert_eq!(u8::MAX, pin_debouncer.held_ticks());
+        assert_eq!(2550, pin_debouncer.hold_ms(10));
+    }
+
+    #[test]
+    fn pin_preload() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 8);
+        pin_debouncer.preload(true);
+        assert!(pin_debouncer.is_warmed_up());
+        assert!(!pin_debouncer.window_complete());
         assert_eq!(BtnState::Pressed, pin_debouncer.get_state());
+        assert_eq!(4, pin_debouncer.held_ticks());
 
-        for _ in 0..8 {
+        for _ in 0..4 {
             pin_debouncer.update(true);
         }
+        assert!(pin_debouncer.window_complete());
         assert_eq!(BtnState::Hold, pin_debouncer.get_state());
 
-        for _ in 0..20 {
+        pin_debouncer.preload(false);
+        assert!(!pin_debouncer.window_complete());
+        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        assert!(!pin_debouncer.just_released());
+        for _ in 0..4 {
             pin_debouncer.update(true);
         }
-        assert_eq!(BtnState::Repeat, pin_debouncer.get_state());
-        assert_eq!(BtnState::Hold, pin_debouncer.get_state());
+        assert_eq!(BtnState::ChangedToPressed, pin_debouncer.get_state());
+    }
 
+    #[test]
+    fn port_pack_states() {
+        let mut port_debouncer: PortDebouncer<U4, U3> = PortDebouncer::new(4, 8);
         for _ in 0..4 {
-            pin_debouncer.update(false);
+            port_debouncer.update(0b101);
         }
-        assert_eq!(BtnState::UnPressed, pin_debouncer.get_state());
+        let mut out = [0xff; 3];
+        assert!(port_debouncer.pack_states(&mut out[..1]).is_err());
+        assert_eq!(0xff, out[0]);
+        assert_eq!(2, port_debouncer.pack_states(&mut out).unwrap());
+        // ChangedToPressed (4), UnPressed (1), ChangedToPressed (4) across the byte boundary
+        assert_eq!([0b00_001_100, 0b1, 0xff], out);
+        // Packing doesn't consume the edges
+        assert_eq!(2, port_debouncer.pack_states(&mut out).unwrap());
+        assert_eq!([0b00_001_100, 0b1, 0xff], out);
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            port_debouncer.get_state(0).unwrap()
+        );
+
+        for _ in 0..4 {
+            port_debouncer.update(0b100);
+        }
+        assert_eq!(2, port_debouncer.pack_states(&mut out).unwrap());
+        // UnPressed (1), UnPressed (1), Hold (3)
+        assert_eq!([0b11_001_001, 0b0, 0xff], out);
     }
 }
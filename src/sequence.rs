@@ -0,0 +1,283 @@
+//! Ordered multi-button gestures on top of `PortDebouncer`
+
+#[allow(deprecated)]
+use generic_array::sequence::GenericSequence;
+use generic_array::typenum::{Unsigned, U0};
+#[allow(deprecated)]
+use generic_array::{ArrayLength, GenericArray};
+
+use crate::{BtnState, Error, HistoryEntry, PortDebouncer, PortWord, Ticks};
+
+/// Step of a sequence: the pin and the state it must reach
+pub type SequenceStep = (usize, BtnState);
+
+/// Wraps a `PortDebouncer` and detects an ordered sequence of button states, e.g. "press A then
+/// hold B". The states of all buttons are read once per window and the sequence advances when
+/// the next expected pin reaches its expected state, at most one step per window. The whole
+/// sequence must be completed within a time window counted from its first step, otherwise the
+/// progress is reset. With `set_keep_held`, the progress is also reset when the pin of a step
+/// already done is released, for gestures such as "press A then, while still holding it, hold B"
+///
+/// Since the states are read by the wrapper, they must be queried from its `get_state` method
+/// rather than from the inner debouncer
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct SequenceDebouncer<
+    N,
+    BTNS,
+    STEPS,
+    HIST = U0,
+    W = u32,
+    BNC = U0,
+    T = u32,
+    WIN = U0,
+    THR = U0,
+    TRN = U0,
+> where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    STEPS: ArrayLength<SequenceStep> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    debouncer: PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>,
+    states: GenericArray<BtnState, BTNS>,
+    steps: GenericArray<SequenceStep, STEPS>,
+    len: usize,
+    window_ticks: u32,
+    progress: usize,
+    elapsed: u32,
+    keep_held: bool,
+}
+
+#[allow(deprecated)]
+impl<N, BTNS, STEPS, HIST, W, BNC, T, WIN, THR, TRN>
+    SequenceDebouncer<N, BTNS, STEPS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    STEPS: ArrayLength<SequenceStep> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    /// Returns a SequenceDebouncer struct with no sequence registered
+    ///
+    /// # Generic arguments
+    ///
+    /// * `STEPS` - Maximum number of steps of the sequence
+    ///
+    /// # Arguments
+    ///
+    /// * `debouncer` - The debouncer to be wrapped
+    pub fn new(debouncer: PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>) -> Self {
+        SequenceDebouncer {
+            debouncer,
+            states: GenericArray::generate(|_| BtnState::UnPressed),
+            steps: GenericArray::generate(|_| (0, BtnState::UnPressed)),
+            len: 0,
+            window_ticks: 0,
+            progress: 0,
+            elapsed: 0,
+            keep_held: false,
+        }
+    }
+
+    /// Registers the sequence to be detected, replacing the previous one and its progress
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The pins and the states they must reach, in order
+    /// * `window_ticks` - The number of ticks, i.e. calls to `update`, allowed between the first
+    ///   and the last step
+    pub fn register_sequence(
+        &mut self,
+        steps: &[SequenceStep],
+        window_ticks: u32,
+    ) -> Result<(), Error> {
+        if steps.len() > STEPS::USIZE {
+            return Err(Error::SequenceTooLong);
+        }
+        for &(pin, _) in steps {
+            PortDebouncer::<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>::check_pin(pin)?;
+        }
+        self.steps[..steps.len()].copy_from_slice(steps);
+        self.len = steps.len();
+        self.window_ticks = window_ticks;
+        self.progress = 0;
+        Ok(())
+    }
+
+    /// Sets whether the pins of the steps already done must stay pressed until the sequence is
+    /// completed. When one of them is released, the progress is reset. Disabled by default, so
+    /// the steps are only required to happen in order
+    pub fn set_keep_held(&mut self, keep_held: bool) {
+        self.keep_held = keep_held;
+    }
+
+    /// Calls the `update` method of the inner debouncer and returns true when the registered
+    /// sequence was completed by this call
+    pub fn update(&mut self, port_value: W) -> bool {
+        if self.progress != 0 {
+            self.elapsed = self.elapsed.saturating_add(1);
+            if self.elapsed > self.window_ticks {
+                self.progress = 0;
+            }
+        }
+        if !self.debouncer.update(port_value) {
+            return false;
+        }
+        // The pins past the width of the port word are never pressed
+        for (pin, state) in self.states.iter_mut().enumerate().take(W::BITS) {
+            *state = self.debouncer.get_state(pin).unwrap();
+        }
+        if self.len == 0 {
+            return false;
+        }
+        if self.keep_held
+            && self.steps[..self.progress]
+                .iter()
+                .any(|&(pin, _)| self.states[pin] == BtnState::UnPressed)
+        {
+            self.progress = 0;
+        }
+        let (pin, state) = self.steps[self.progress];
+        if self.states[pin] != state {
+            return false;
+        }
+        if self.progress == 0 {
+            self.elapsed = 0;
+        }
+        self.progress += 1;
+        if self.progress == self.len {
+            self.progress = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Returns the state of the queried pin read in the last completed window
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried
+    pub fn get_state(&self, pin: usize) -> Result<BtnState, Error> {
+        self.states.get(pin).copied().ok_or(Error::BtnUninitialized)
+    }
+
+    /// Returns the number of steps of the registered sequence already done
+    pub fn progress(&self) -> usize {
+        self.progress
+    }
+
+    /// Returns a reference to the inner debouncer
+    pub fn debouncer(&self) -> &PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> {
+        &self.debouncer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use generic_array::typenum::consts::*;
+
+    #[test]
+    fn press_then_hold() {
+        let port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 20);
+        let mut sequence: SequenceDebouncer<_, _, U2> = SequenceDebouncer::new(port_debouncer);
+        sequence
+            .register_sequence(&[(0, BtnState::ChangedToPressed), (1, BtnState::Hold)], 40)
+            .unwrap();
+
+        let mut completed = false;
+        for _ in 0..4 {
+            completed |= sequence.update(0b01);
+        }
+        assert_eq!(1, sequence.progress());
+        for _ in 0..24 {
+            completed |= sequence.update(0b11);
+        }
+        assert!(completed);
+        assert_eq!(0, sequence.progress());
+        assert_eq!(BtnState::Hold, sequence.get_state(1).unwrap());
+    }
+
+    #[test]
+    fn press_then_hold_while_held() {
+        let port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 20);
+        let mut sequence: SequenceDebouncer<_, _, U2> = SequenceDebouncer::new(port_debouncer);
+        sequence
+            .register_sequence(&[(0, BtnState::ChangedToPressed), (1, BtnState::Hold)], 40)
+            .unwrap();
+        sequence.set_keep_held(true);
+
+        // Releasing the first pin before the second one is held starts over
+        let mut completed = false;
+        for _ in 0..4 {
+            completed |= sequence.update(0b01);
+        }
+        assert_eq!(1, sequence.progress());
+        for _ in 0..24 {
+            completed |= sequence.update(0b10);
+        }
+        assert!(!completed);
+        assert_eq!(0, sequence.progress());
+
+        for _ in 0..4 {
+            completed |= sequence.update(0b00);
+        }
+        for _ in 0..4 {
+            completed |= sequence.update(0b01);
+        }
+        for _ in 0..24 {
+            completed |= sequence.update(0b11);
+        }
+        assert!(completed);
+    }
+
+    #[test]
+    fn sequence_timeout() {
+        let port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 20);
+        let mut sequence: SequenceDebouncer<_, _, U2> = SequenceDebouncer::new(port_debouncer);
+        sequence
+            .register_sequence(&[(0, BtnState::ChangedToPressed), (1, BtnState::Hold)], 16)
+            .unwrap();
+
+        let mut completed = false;
+        for _ in 0..4 {
+            completed |= sequence.update(0b01);
+        }
+        for _ in 0..24 {
+            completed |= sequence.update(0b11);
+        }
+        assert!(!completed);
+        assert_eq!(0, sequence.progress());
+        assert!(sequence
+            .register_sequence(&[(0, BtnState::Hold); 3], 16)
+            .is_err());
+    }
+
+    #[test]
+    fn more_buttons_than_bits() {
+        let port_debouncer: PortDebouncer<U4, U40> = PortDebouncer::new(20, 20);
+        let mut sequence: SequenceDebouncer<_, _, U2> = SequenceDebouncer::new(port_debouncer);
+        assert!(sequence
+            .register_sequence(&[(0, BtnState::ChangedToPressed), (35, BtnState::Hold)], 40)
+            .is_err());
+        for _ in 0..4 {
+            sequence.update(u32::MAX);
+        }
+        assert_eq!(BtnState::ChangedToPressed, sequence.get_state(31).unwrap());
+        assert_eq!(BtnState::UnPressed, sequence.get_state(35).unwrap());
+    }
+}
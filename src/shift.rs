@@ -0,0 +1,163 @@
+//! Port debouncing with a per pin shift register, for long windows and few buttons
+
+use crate::{BtnState, Error};
+use core::marker::PhantomData;
+#[allow(deprecated)]
+use generic_array::{typenum::Unsigned, ArrayLength, GenericArray};
+
+/// Alternative to `PortDebouncer` keeping the samples of each pin in a shift register, i.e. one
+/// `u32` per button holding its last 32 samples, instead of one port word per sample. The memory
+/// used doesn't depend on `N`, which makes it cheaper for long windows and few buttons, e.g. 8
+/// bytes for two buttons and a window of 32 samples
+///
+/// The debounced state follows the same AND semantics as a `PortDebouncer` with the default
+/// `DebounceMode::AllAgree`: it's updated at the end of each window of `N` samples and a button
+/// is pressed if all the samples of the window agree. Only the `ChangedToPressed`, `Pressed` and
+/// `UnPressed` states are reported, there's no hold or repeat
+///
+/// # Type Parameters
+///
+/// * `N` - Number of samples of a window, from 1 to 32
+/// * `BTNS` - Number of buttons, at most 32
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct ShiftPortDebouncer<N, BTNS: ArrayLength<u32>> {
+    history: GenericArray<u32, BTNS>,
+    current_index: usize,
+    debounced_state: u32,
+    unread_presses: u32,
+    _window: PhantomData<N>,
+}
+
+#[allow(deprecated)]
+impl<N: Unsigned, BTNS: ArrayLength<u32>> ShiftPortDebouncer<N, BTNS> {
+    /// Returns a ShiftPortDebouncer struct, with all the buttons released. Returns
+    /// `Error::TooManyButtons` if `BTNS` is above 32, or `Error::InvalidWindow` if `N` isn't
+    /// between 1 and 32
+    pub fn new() -> Result<ShiftPortDebouncer<N, BTNS>, Error> {
+        if BTNS::USIZE > 32 {
+            return Err(Error::TooManyButtons);
+        }
+        if N::USIZE == 0 || N::USIZE > 32 {
+            return Err(Error::InvalidWindow);
+        }
+        Ok(ShiftPortDebouncer {
+            history: GenericArray::default(),
+            current_index: 0,
+            debounced_state: 0,
+            unread_presses: 0,
+            _window: PhantomData,
+        })
+    }
+
+    /// Feeds a new sample of the port, same as `PortDebouncer::update`. Returns true when this
+    /// call completed a window, i.e. the debounced state was just updated
+    ///
+    /// # Arguments
+    ///
+    /// * `port_value` - The port state in a given time, where its bits represent a pin state, the
+    ///   pins being considered active-high
+    pub fn update(&mut self, port_value: u32) -> bool {
+        for (index, history) in self.history.iter_mut().enumerate() {
+            *history = (*history << 1) | ((port_value >> index) & 1);
+        }
+        self.current_index += 1;
+        if self.current_index != N::USIZE {
+            return false;
+        }
+        self.current_index = 0;
+        let mask = u32::MAX >> (32 - N::USIZE);
+        let mut state = 0;
+        for (index, &history) in self.history.iter().enumerate() {
+            if history & mask == mask {
+                state |= 1 << index;
+            }
+        }
+        self.unread_presses = state & !self.debounced_state;
+        self.debounced_state = state;
+        true
+    }
+
+    /// Returns the state of the queried pin according to the last completed window. A
+    /// `ChangedToPressed` is consumed by this call, a second call in the same window returns
+    /// `Pressed`. Returns `Error::BtnUninitialized` if `pin` isn't below `BTNS`
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin which state must be queried. Where the zeroth pin is considered to be the least
+    ///   significant bit in the `port_value` used in the `update` method
+    pub fn get_state(&mut self, pin: usize) -> Result<BtnState, Error> {
+        if pin >= BTNS::USIZE {
+            return Err(Error::BtnUninitialized);
+        }
+        let mask = 1 << pin;
+        if self.unread_presses & mask != 0 {
+            self.unread_presses &= !mask;
+            Ok(BtnState::ChangedToPressed)
+        } else if self.debounced_state & mask != 0 {
+            Ok(BtnState::Pressed)
+        } else {
+            Ok(BtnState::UnPressed)
+        }
+    }
+
+    /// Returns the debounced state of the port from the last completed window, one bit per pin
+    pub fn debounced_state(&self) -> u32 {
+        self.debounced_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::PortDebouncer;
+    use generic_array::typenum::{U0, U2, U32, U33, U4};
+
+    #[test]
+    fn shift_matches_port_debouncer() {
+        let mut shift: ShiftPortDebouncer<U4, U2> = ShiftPortDebouncer::new().unwrap();
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(1000, 1000);
+        // Simple LCG to get a noisy input with long runs
+        let mut seed: u32 = 7;
+        let mut value = 0;
+        for _ in 0..1000 {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            if (seed >> 16) & 0b11 == 0 {
+                value = (seed >> 24) & 0b11;
+            }
+            assert_eq!(port_debouncer.update(value), shift.update(value));
+            for pin in 0..2 {
+                assert_eq!(
+                    port_debouncer.get_state(pin).unwrap(),
+                    shift.get_state(pin).unwrap()
+                );
+            }
+        }
+        assert!(shift.get_state(2).is_err());
+    }
+
+    #[test]
+    fn shift_long_window() {
+        let mut shift: ShiftPortDebouncer<U32, U2> = ShiftPortDebouncer::new().unwrap();
+        for _ in 0..31 {
+            assert!(!shift.update(0b01));
+        }
+        assert!(shift.update(0b11));
+        assert_eq!(BtnState::ChangedToPressed, shift.get_state(0).unwrap());
+        assert_eq!(BtnState::UnPressed, shift.get_state(1).unwrap());
+        assert_eq!(0b01, shift.debounced_state());
+    }
+
+    #[test]
+    fn shift_window_range() {
+        assert!(matches!(
+            ShiftPortDebouncer::<U0, U2>::new(),
+            Err(Error::InvalidWindow)
+        ));
+        assert!(matches!(
+            ShiftPortDebouncer::<U33, U2>::new(),
+            Err(Error::InvalidWindow)
+        ));
+    }
+}
@@ -0,0 +1,53 @@
+//! Offline simulation of the debouncers, to check timing choices against recorded traces
+
+use std::vec::Vec;
+
+use crate::{BtnState, PinConfig, PinDebouncer};
+
+/// Runs a `PinDebouncer` configured by `cfg` over a raw trace, one sample per tick, and returns
+/// the state read after each completed window, as a live application calling `get_state` once
+/// per window would see it
+///
+/// ```rust
+/// use debouncer::sim::simulate;
+/// use debouncer::{BtnState, PinConfig};
+///
+/// let cfg = PinConfig { press_ticks: 2, ..Default::default() };
+/// let states = simulate(&cfg, &[true, false, true, true, true, true]);
+/// assert_eq!(
+///     vec![BtnState::UnPressed, BtnState::ChangedToPressed, BtnState::Pressed],
+///     states
+/// );
+/// ```
+pub fn simulate(cfg: &PinConfig, raw: &[bool]) -> Vec<BtnState> {
+    let mut debouncer = PinDebouncer::from_config(*cfg);
+    raw.iter()
+        .filter_map(|&sample| debouncer.tick(sample))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn simulate_bouncy_trace() {
+        let cfg = PinConfig {
+            press_ticks: 4,
+            ..Default::default()
+        };
+        let raw = [
+            true, false, true, false, true, true, true, true, true, true, true, true,
+        ];
+        assert_eq!(
+            [
+                BtnState::UnPressed,
+                BtnState::ChangedToPressed,
+                BtnState::Pressed
+            ],
+            simulate(&cfg, &raw)[..]
+        );
+        assert!(simulate(&cfg, &raw[..3]).is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+//! Common query interface over the different kinds of debouncers
+
+use generic_array::typenum::Unsigned;
+#[allow(deprecated)]
+use generic_array::ArrayLength;
+
+use crate::{
+    AnalogDebouncer, BtnState, Error, HistoryEntry, PinDebouncer, PortDebouncer, PortWord, Ticks,
+};
+
+/// Source of a single button state, implemented by `PinDebouncer`, `AnalogDebouncer` and the
+/// single pin view of a `PortDebouncer` returned by `PortDebouncer::channel`, so different kinds
+/// of inputs can be stored as `&mut dyn StateSource` and queried the same way
+pub trait StateSource {
+    /// Returns the debounced state, consuming a pending `Repeat` as the `get_state` method of the
+    /// implementor does
+    fn state(&mut self) -> BtnState;
+}
+
+impl<T: Ticks> StateSource for PinDebouncer<T> {
+    fn state(&mut self) -> BtnState {
+        self.get_state()
+    }
+}
+
+impl<T: Ticks> StateSource for AnalogDebouncer<T> {
+    fn state(&mut self) -> BtnState {
+        self.get_state()
+    }
+}
+
+/// View of a single pin of a `PortDebouncer`, as returned by `PortDebouncer::channel`
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct PortChannel<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    debouncer: &'a mut PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>,
+    pin: usize,
+}
+
+#[allow(deprecated)]
+impl<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+    PortChannel<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    /// Returns the pin of the view
+    pub fn pin(&self) -> usize {
+        self.pin
+    }
+}
+
+#[allow(deprecated)]
+impl<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> StateSource
+    for PortChannel<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    fn state(&mut self) -> BtnState {
+        // The pin was checked when the view was created
+        self.debouncer
+            .get_state(self.pin)
+            .unwrap_or(BtnState::UnPressed)
+    }
+}
+
+#[allow(deprecated)]
+impl<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    /// Returns a view of a single pin implementing `StateSource`
+    ///
+    /// # Arguments
+    ///
+    /// * `pin` - Pin to be viewed. Where the zeroth pin is considered to be the least significant
+    ///   bit in the `port_value` used in the `update` method
+    #[allow(clippy::type_complexity)]
+    pub fn channel(
+        &mut self,
+        pin: usize,
+    ) -> Result<PortChannel<'_, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>, Error> {
+        Self::check_pin(pin)?;
+        Ok(PortChannel {
+            debouncer: self,
+            pin,
+        })
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_must_use)]
+mod tests {
+
+    use super::*;
+    use generic_array::typenum::{U2, U4};
+
+    #[test]
+    fn state_sources() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+        let mut analog_debouncer = AnalogDebouncer::new(4, 20, 100, 1000, 100);
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        for _ in 0..4 {
+            pin_debouncer.update(true);
+            analog_debouncer.update(500);
+            port_debouncer.update(0b10);
+        }
+        assert!(port_debouncer.channel(2).is_err());
+
+        let mut channel = port_debouncer.channel(1).unwrap();
+        assert_eq!(1, channel.pin());
+        let mut sources: [&mut dyn StateSource; 3] =
+            [&mut pin_debouncer, &mut analog_debouncer, &mut channel];
+        let mut states = [BtnState::Pressed; 3];
+        for (state, source) in states.iter_mut().zip(sources.iter_mut()) {
+            *state = source.state();
+        }
+        assert_eq!(
+            [
+                BtnState::ChangedToPressed,
+                BtnState::UnPressed,
+                BtnState::ChangedToPressed,
+            ],
+            states
+        );
+    }
+}
@@ -0,0 +1,46 @@
+//! Helpers for writing tests on top of the debouncers
+
+use crate::{BtnState, PinDebouncer, Ticks};
+
+/// Feeds `debouncer` with each value of `pattern`, repeated for `ticks_each` calls to `update`,
+/// and returns the state read with `get_state` after the last one
+///
+/// # Arguments
+///
+/// * `debouncer` - The debouncer under test
+/// * `pattern` - The pin values, in order
+/// * `ticks_each` - For how many ticks each value of `pattern` is held
+pub fn drive<T: Ticks>(
+    debouncer: &mut PinDebouncer<T>,
+    pattern: &[bool],
+    ticks_each: usize,
+) -> BtnState {
+    for &value in pattern {
+        for _ in 0..ticks_each {
+            let _ = debouncer.update(value);
+        }
+    }
+    debouncer.get_state()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn drive_pattern() {
+        let mut pin_debouncer = PinDebouncer::new(4, 20, 100);
+
+        assert_eq!(
+            BtnState::UnPressed,
+            drive(&mut pin_debouncer, &[true, false], 2)
+        );
+        assert_eq!(
+            BtnState::ChangedToPressed,
+            drive(&mut pin_debouncer, &[true], 4)
+        );
+        assert_eq!(BtnState::Hold, drive(&mut pin_debouncer, &[true], 96));
+        assert_eq!(BtnState::UnPressed, drive(&mut pin_debouncer, &[false], 4));
+    }
+}
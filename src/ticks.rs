@@ -0,0 +1,69 @@
+//! Unsigned integer types usable as the tick counters of the debouncers
+
+use core::fmt::Debug;
+use core::hash::Hash;
+
+/// Unsigned integer counting the ticks of a `PinDebouncer` or of each button of a
+/// `PortDebouncer`, i.e. their thresholds, counters and measured durations. It's `u32` by
+/// default, a smaller type saves memory on tiny targets, e.g. for the per pin counters of a port
+/// with many buttons, and a bigger one allows measuring longer holds. The counters saturate at
+/// the maximum of the type, so the thresholds must stay below it, otherwise they're never
+/// reached:
+///
+/// | Ticks   | Longest measured hold  | At a 1ms tick     |
+/// |---------|------------------------|-------------------|
+/// | `u8`    | 255 ticks              | 255 milliseconds  |
+/// | `u16`   | 65535 ticks            | 65 seconds        |
+/// | `u32`   | 4294967295 ticks       | 49 days           |
+/// | `u64`   | 2^64 - 1 ticks         | 584 million years |
+/// | `usize` | Same as `u32` or `u64` | Target dependent  |
+///
+/// e.g. a `PinDebouncer<u64>` is created with `PinDebouncer::with_ticks` and a
+/// `PortDebouncer<U4, U8, U0, u8, U0, u16>` keeps its counters in `u16`
+pub trait Ticks: Copy + Default + Ord + Hash + Debug {
+    /// No tick
+    const ZERO: Self;
+    /// A single tick
+    const ONE: Self;
+
+    /// Returns `self + rhs`, saturated at the maximum of the type
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Returns `self - rhs`, saturated at zero
+    fn saturating_sub(self, rhs: Self) -> Self;
+
+    /// Returns `value` ticks, saturated at the maximum of the type
+    fn from_usize(value: usize) -> Self;
+
+    /// Returns the number of ticks as a `u64`, saturated at `u64::MAX`
+    fn to_u64(self) -> u64;
+}
+
+macro_rules! impl_ticks {
+    ($($ticks:ty),*) => {
+        $(
+            impl Ticks for $ticks {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn saturating_add(self, rhs: Self) -> Self {
+                    <$ticks>::saturating_add(self, rhs)
+                }
+
+                fn saturating_sub(self, rhs: Self) -> Self {
+                    <$ticks>::saturating_sub(self, rhs)
+                }
+
+                fn from_usize(value: usize) -> Self {
+                    core::convert::TryFrom::try_from(value).unwrap_or(<$ticks>::MAX)
+                }
+
+                fn to_u64(self) -> u64 {
+                    core::convert::TryFrom::try_from(self).unwrap_or(u64::MAX)
+                }
+            }
+        )*
+    };
+}
+
+impl_ticks!(u8, u16, u32, u64, usize);
@@ -0,0 +1,118 @@
+//! Wall-clock driven debouncing for hosted targets
+
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+
+use crate::{BtnState, Error, PinDebouncer, Ticks};
+
+/// Wraps a `PinDebouncer` and feeds it according to the real time elapsed between calls, instead
+/// of one tick per call. This allows the same tick based configuration to be used in loops with
+/// variable timing, e.g. on a Linux SBC
+///
+/// As with `ClockedPinDebouncer`, the pin is assumed to keep its level between calls, so the
+/// elapsed ticks are fed with the level given in the previous call
+#[derive(Debug)]
+pub struct TimedDebouncer<T: Ticks = u32> {
+    debouncer: PinDebouncer<T>,
+    tick_period: Duration,
+    last_tick: Instant,
+    level: bool,
+}
+
+impl<T: Ticks> TimedDebouncer<T> {
+    /// Returns a TimedDebouncer struct, starting with the pin released. Returns
+    /// `Error::ZeroTickPeriod` if `tick_period` is zero
+    ///
+    /// # Arguments
+    ///
+    /// * `debouncer` - The debouncer to be fed, with all its timings given in ticks
+    /// * `tick_period` - The real time duration of one tick, must be non-zero
+    pub fn new(
+        debouncer: PinDebouncer<T>,
+        tick_period: Duration,
+    ) -> Result<TimedDebouncer<T>, Error> {
+        if tick_period == Duration::from_secs(0) {
+            return Err(Error::ZeroTickPeriod);
+        }
+        // The level read from a released pin, high for an active-low one
+        let level = debouncer.active_low;
+        Ok(TimedDebouncer {
+            debouncer,
+            tick_period,
+            last_tick: Instant::now(),
+            level,
+        })
+    }
+
+    /// Calls the `update` method of the inner debouncer once for each tick period elapsed since
+    /// the last tick, using the level of the previous call as the sample, then records `pressed`
+    /// as the current level. The ticks are replayed as by `ClockedPinDebouncer::update`, and the
+    /// fraction of a tick left is carried to the next call. Returns true if any of the updates
+    /// completed a window
+    pub fn update_elapsed(&mut self, pressed: bool) -> bool {
+        let elapsed = self.last_tick.elapsed();
+        let ticks =
+            u32::try_from(elapsed.as_nanos() / self.tick_period.as_nanos()).unwrap_or(u32::MAX);
+        let completed = self.debouncer.replay(self.level, u64::from(ticks));
+        self.last_tick += self.tick_period * ticks;
+        self.level = pressed;
+        completed
+    }
+
+    /// Returns the state of the inner debouncer, see `PinDebouncer::get_state`
+    pub fn get_state(&mut self) -> BtnState {
+        self.debouncer.get_state()
+    }
+
+    /// Returns a reference to the inner debouncer
+    pub fn debouncer(&self) -> &PinDebouncer<T> {
+        &self.debouncer
+    }
+
+    /// Returns a mutable reference to the inner debouncer
+    pub fn debouncer_mut(&mut self) -> &mut PinDebouncer<T> {
+        &mut self.debouncer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn timed_elapsed_ticks() {
+        let mut timed =
+            TimedDebouncer::new(PinDebouncer::new(4, 20, 100), Duration::from_millis(1)).unwrap();
+
+        // The elapsed ticks are fed with the level of the previous call
+        timed.update_elapsed(true);
+        thread::sleep(Duration::from_millis(10));
+        assert!(timed.update_elapsed(false));
+        assert_ne!(BtnState::UnPressed, timed.get_state());
+        thread::sleep(Duration::from_millis(10));
+        assert!(timed.update_elapsed(false));
+        assert_eq!(BtnState::UnPressed, timed.get_state());
+    }
+
+    #[test]
+    fn timed_active_low() {
+        let mut timed = TimedDebouncer::new(
+            PinDebouncer::new_active_low(4, 20, 100),
+            Duration::from_millis(1),
+        )
+        .unwrap();
+        thread::sleep(Duration::from_millis(10));
+        assert!(timed.update_elapsed(true));
+        assert_eq!(BtnState::UnPressed, timed.get_state());
+    }
+
+    #[test]
+    fn timed_zero_period() {
+        assert!(matches!(
+            TimedDebouncer::new(PinDebouncer::new(4, 20, 100), Duration::from_secs(0)),
+            Err(Error::ZeroTickPeriod)
+        ));
+    }
+}
@@ -0,0 +1,93 @@
+//! Latched on/off state driven by momentary buttons
+
+use crate::{PinDebouncer, Ticks};
+
+/// Wraps a `PinDebouncer` and latches an on/off state flipped on each debounced press, i.e. each
+/// `ChangedToPressed`, for momentary buttons used as toggles. The state starts off. See
+/// `PortDebouncer::toggled` for the port version
+#[derive(Debug)]
+pub struct ToggleDebouncer<T: Ticks = u32> {
+    debouncer: PinDebouncer<T>,
+    pressed: bool,
+    on: bool,
+}
+
+impl<T: Ticks> ToggleDebouncer<T> {
+    /// Returns a ToggleDebouncer struct
+    ///
+    /// # Arguments
+    ///
+    /// * `debouncer` - The debouncer to be fed through `update`
+    pub const fn new(debouncer: PinDebouncer<T>) -> ToggleDebouncer<T> {
+        ToggleDebouncer {
+            debouncer,
+            pressed: false,
+            on: false,
+        }
+    }
+
+    /// Feeds a new sample of the pin, see `PinDebouncer::update`. The state is flipped when this
+    /// call completes a window in which the button was pressed
+    pub fn update(&mut self, pressed: bool) -> bool {
+        if !self.debouncer.update(pressed) {
+            return false;
+        }
+        let level = self.debouncer.level();
+        if level && !self.pressed {
+            self.on = !self.on;
+        }
+        self.pressed = level;
+        true
+    }
+
+    /// Returns the latched state
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Overrides the latched state, e.g. to restore it at startup. The next press flips it as
+    /// usual
+    pub fn set(&mut self, on: bool) {
+        self.on = on;
+    }
+
+    /// Returns a reference to the inner debouncer
+    pub fn debouncer(&self) -> &PinDebouncer<T> {
+        &self.debouncer
+    }
+
+    /// Returns a mutable reference to the inner debouncer
+    pub fn debouncer_mut(&mut self) -> &mut PinDebouncer<T> {
+        &mut self.debouncer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn toggle_flips_on_press() {
+        let mut toggle = ToggleDebouncer::new(PinDebouncer::new(4, 20, 100));
+        assert!(!toggle.is_on());
+
+        for _ in 0..12 {
+            toggle.update(true);
+        }
+        assert!(toggle.is_on());
+
+        for _ in 0..4 {
+            toggle.update(false);
+        }
+        assert!(toggle.is_on());
+
+        for _ in 0..4 {
+            toggle.update(true);
+        }
+        assert!(!toggle.is_on());
+
+        toggle.set(true);
+        assert!(toggle.is_on());
+    }
+}
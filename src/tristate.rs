@@ -0,0 +1,88 @@
+//! Debouncing of three position switches
+
+use crate::{PinDebouncer, Ticks};
+
+/// Stable position of a three position switch, as returned by `TriStateDebouncer::get_state`
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum SwitchPosition {
+    Left,
+    Center,
+    Right,
+}
+
+/// Debounces a center-off SPDT switch wired to two pins, one for each contact. Each contact is
+/// debounced by its own `PinDebouncer` and the position is `Center` while none or both of them
+/// are closed, the latter being an invalid reading, e.g. a contact bouncing while the lever
+/// moves from one side to the other
+#[derive(Debug)]
+pub struct TriStateDebouncer<T: Ticks = u32> {
+    left: PinDebouncer<T>,
+    right: PinDebouncer<T>,
+}
+
+impl TriStateDebouncer {
+    /// Returns a TriStateDebouncer struct
+    ///
+    /// # Arguments
+    ///
+    /// * `press_ticks` - Same as in `PinDebouncer::new`, the hold and repeat states aren't used
+    pub const fn new(press_ticks: u32) -> TriStateDebouncer {
+        TriStateDebouncer {
+            left: PinDebouncer::new(press_ticks, 1, press_ticks),
+            right: PinDebouncer::new(press_ticks, 1, press_ticks),
+        }
+    }
+}
+
+impl<T: Ticks> TriStateDebouncer<T> {
+    /// Same as `new`, but counting the ticks with any `Ticks` type, see
+    /// `PinDebouncer::with_ticks`
+    pub fn with_ticks(press_ticks: T) -> TriStateDebouncer<T> {
+        TriStateDebouncer {
+            left: PinDebouncer::with_ticks(press_ticks, T::ONE, press_ticks),
+            right: PinDebouncer::with_ticks(press_ticks, T::ONE, press_ticks),
+        }
+    }
+
+    /// Feeds a new sample of both contacts, this method should be called every tick. Returns true
+    /// when this call completed a window, see `PinDebouncer::update`
+    pub fn update(&mut self, left: bool, right: bool) -> bool {
+        self.right.update(right) | self.left.update(left)
+    }
+
+    /// Returns the debounced position of the switch, according to the last completed window
+    pub fn get_state(&self) -> SwitchPosition {
+        match (self.left.level(), self.right.level()) {
+            (true, false) => SwitchPosition::Left,
+            (false, true) => SwitchPosition::Right,
+            _ => SwitchPosition::Center,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn tristate_positions() {
+        let mut switch = TriStateDebouncer::new(4);
+        assert_eq!(SwitchPosition::Center, switch.get_state());
+
+        for _ in 0..4 {
+            switch.update(true, false);
+        }
+        assert_eq!(SwitchPosition::Left, switch.get_state());
+
+        for _ in 0..4 {
+            switch.update(true, true);
+        }
+        assert_eq!(SwitchPosition::Center, switch.get_state());
+
+        for _ in 0..8 {
+            switch.update(false, true);
+        }
+        assert_eq!(SwitchPosition::Right, switch.get_state());
+    }
+}
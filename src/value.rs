@@ -0,0 +1,80 @@
+//! Debouncing of multi-valued signals, e.g. an encoded selector switch
+
+#[allow(deprecated)]
+use generic_array::{sequence::GenericSequence, ArrayLength, GenericArray};
+
+/// Debounces a signal taking a small set of values, e.g. a 3-way switch encoded as 0, 1 or 2,
+/// instead of a single boolean level. The last `N` samples are kept and a new debounced value is
+/// only committed once all of them agree, which generalizes the per-bit AND done by
+/// `PortDebouncer` to any comparable value
+#[derive(Debug)]
+#[allow(deprecated)]
+pub struct ValueDebouncer<T: PartialEq + Copy, N: ArrayLength<T>> {
+    samples: GenericArray<T, N>,
+    current_index: usize,
+    value: T,
+}
+
+#[allow(deprecated)]
+impl<T: PartialEq + Copy, N: ArrayLength<T>> ValueDebouncer<T, N> {
+    /// Returns a ValueDebouncer struct, with all the samples set to `initial`
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - The debounced value until `N` samples agree on another one
+    pub fn new(initial: T) -> ValueDebouncer<T, N> {
+        ValueDebouncer {
+            samples: GenericArray::generate(|_| initial),
+            current_index: 0,
+            value: initial,
+        }
+    }
+
+    /// Feeds a new sample of the signal, this method should be called every tick. Returns the
+    /// new debounced value when the last `N` samples agree on a value other than the current one,
+    /// `None` otherwise
+    pub fn update(&mut self, value: T) -> Option<T> {
+        if N::USIZE == 0 {
+            return None;
+        }
+        self.samples[self.current_index] = value;
+        self.current_index = (self.current_index + 1) % N::USIZE;
+        if value != self.value && self.samples.iter().all(|&sample| sample == value) {
+            self.value = value;
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the debounced value
+    pub fn value(&self) -> T {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use generic_array::typenum::U3;
+
+    #[test]
+    fn value_commits_when_all_agree() {
+        let mut selector: ValueDebouncer<u8, U3> = ValueDebouncer::new(0);
+        assert_eq!(None, selector.update(1));
+        assert_eq!(None, selector.update(2));
+        assert_eq!(None, selector.update(1));
+        assert_eq!(None, selector.update(1));
+        assert_eq!(0, selector.value());
+        assert_eq!(Some(1), selector.update(1));
+        assert_eq!(1, selector.value());
+
+        // Already the debounced value
+        assert_eq!(None, selector.update(1));
+        for _ in 0..2 {
+            assert_eq!(None, selector.update(2));
+        }
+        assert_eq!(Some(2), selector.update(2));
+    }
+}
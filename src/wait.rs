@@ -0,0 +1,142 @@
+//! Asynchronous wait for button changes
+
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use generic_array::typenum::Unsigned;
+#[allow(deprecated)]
+use generic_array::ArrayLength;
+
+use crate::{BtnState, HistoryEntry, PortDebouncer, PortWord, Ticks};
+
+#[allow(deprecated)]
+impl<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    /// Returns the lowest enabled pin which was pressed or released since the last change
+    /// returned by this method, along with its current state, either `ChangedToPressed` or
+    /// `UnPressed`. Otherwise the waker of `cx` is stored and woken by the next `update` which
+    /// completes a window with a change
+    ///
+    /// This doesn't keep the debouncer borrowed between polls, so it can be wrapped in a future
+    /// that shares the debouncer with the code calling `update`, see `wait_for_change` for a
+    /// `RefCell`. When `update` runs in an interrupt, the future should instead lock a mutex,
+    /// e.g. a critical section one, around each call to this method
+    pub fn poll_change(&mut self, cx: &mut Context<'_>) -> Poll<(usize, BtnState)> {
+        let pending = self.pending_changes & self.enabled;
+        match (0..BTNS::USIZE).find(|&pin| pending & W::bit(pin) != W::ZERO) {
+            Some(pin) => {
+                self.pending_changes &= !W::bit(pin);
+                if self.debounced_state & W::bit(pin) != W::ZERO {
+                    Poll::Ready((pin, BtnState::ChangedToPressed))
+                } else {
+                    Poll::Ready((pin, BtnState::UnPressed))
+                }
+            }
+            None => {
+                self.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Returns a future resolving to the next change, see `poll_change`. The debouncer is only
+    /// borrowed from `debouncer` while the future is polled, so `update` can keep being called
+    /// through the same cell, e.g. from a sampling task on the same executor
+    ///
+    /// # Panics
+    ///
+    /// The future panics if polled while the cell is already mutably borrowed
+    pub fn wait_for_change(
+        debouncer: &RefCell<Self>,
+    ) -> impl Future<Output = (usize, BtnState)> + '_ {
+        WaitForChange { debouncer }
+    }
+}
+
+struct WaitForChange<'a, D> {
+    debouncer: &'a RefCell<D>,
+}
+
+#[allow(deprecated)]
+impl<'a, N, BTNS, HIST, W, BNC, T, WIN, THR, TRN> Future
+    for WaitForChange<'a, PortDebouncer<N, BTNS, HIST, W, BNC, T, WIN, THR, TRN>>
+where
+    N: ArrayLength<W> + Unsigned,
+    BTNS: ArrayLength<u32> + ArrayLength<T> + ArrayLength<BtnState> + Unsigned,
+    HIST: ArrayLength<HistoryEntry> + Unsigned,
+    W: PortWord,
+    BNC: ArrayLength<u32> + Unsigned,
+    T: Ticks,
+    WIN: ArrayLength<u32> + Unsigned,
+    THR: ArrayLength<T> + Unsigned,
+    TRN: ArrayLength<BtnState> + Unsigned,
+{
+    type Output = (usize, BtnState);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.debouncer.borrow_mut().poll_change(cx)
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_must_use)]
+mod tests {
+
+    use core::task::{RawWaker, RawWakerVTable, Waker};
+
+    use super::*;
+    use generic_array::typenum::{U2, U4};
+
+    fn noop_waker() -> Waker {
+        fn clone(_: *const ()) -> RawWaker {
+            RawWaker::new(core::ptr::null(), &VTABLE)
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) }
+    }
+
+    #[test]
+    fn wait_for_change_resolves() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut port_debouncer: PortDebouncer<U4, U2> = PortDebouncer::new(20, 100);
+        assert_eq!(Poll::Pending, port_debouncer.poll_change(&mut cx));
+
+        for _ in 0..4 {
+            port_debouncer.update(0b10);
+        }
+        assert_eq!(
+            Poll::Ready((1, BtnState::ChangedToPressed)),
+            port_debouncer.poll_change(&mut cx)
+        );
+
+        // The future stays pending until a change is fed through the shared cell
+        let port_debouncer = RefCell::new(port_debouncer);
+        let mut future = core::pin::pin!(PortDebouncer::wait_for_change(&port_debouncer));
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut cx));
+        for _ in 0..4 {
+            port_debouncer.borrow_mut().update(0b10);
+        }
+        assert_eq!(Poll::Pending, future.as_mut().poll(&mut cx));
+        for _ in 0..4 {
+            port_debouncer.borrow_mut().update(0b00);
+        }
+        assert_eq!(
+            Poll::Ready((1, BtnState::UnPressed)),
+            future.as_mut().poll(&mut cx)
+        );
+    }
+}
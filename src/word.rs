@@ -0,0 +1,92 @@
+//! Unsigned integer types usable as the port value of a `PortDebouncer`
+
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, Not};
+
+/// Unsigned integer holding one sample of the port, where each bit represents a pin. The word
+/// width limits the number of buttons a `PortDebouncer` can handle, but a smaller word also
+/// reduces the memory used to store the `N` samples of the window and the other per port masks:
+///
+/// | Word  | Max buttons | Samples memory |
+/// |-------|-------------|----------------|
+/// | `u8`  | 8           | `N` bytes      |
+/// | `u16` | 16          | `2 * N` bytes  |
+/// | `u32` | 32          | `4 * N` bytes  |
+/// | `u64` | 64          | `8 * N` bytes  |
+///
+/// e.g. a `PortDebouncer<U8, U8, U0, u16>` uses 16 bytes for its samples instead of 32. The per
+/// pin state, such as the counters and the tick thresholds, doesn't depend on the word, so the
+/// struct as a whole only shrinks by those few bytes
+pub trait PortWord:
+    Copy
+    + Default
+    + Eq
+    + Hash
+    + Debug
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+{
+    /// Number of bits in the word, i.e. the maximum number of buttons
+    const BITS: usize;
+    /// Word with all bits cleared
+    const ZERO: Self;
+    /// Word with all bits set
+    const ONES: Self;
+
+    /// Returns a word with only the `index` bit set, or no bit at all if `index` isn't lower than
+    /// `BITS`, so pins past the word width are never considered pressed
+    fn bit(index: usize) -> Self;
+
+    /// Returns a word with the `count` least significant bits set, or all of them if `count` is
+    /// greater than `BITS`
+    fn low_bits(count: usize) -> Self;
+
+    /// Returns the number of bits set
+    fn count_ones(self) -> u32;
+
+    /// Returns the index of the least significant bit set, or `BITS` if none is set
+    fn trailing_zeros(self) -> u32;
+}
+
+macro_rules! impl_port_word {
+    ($($word:ty),*) => {
+        $(
+            impl PortWord for $word {
+                const BITS: usize = <$word>::BITS as usize;
+                const ZERO: Self = 0;
+                const ONES: Self = <$word>::MAX;
+
+                fn bit(index: usize) -> Self {
+                    if index < <Self as PortWord>::BITS {
+                        1 << index
+                    } else {
+                        0
+                    }
+                }
+
+                fn low_bits(count: usize) -> Self {
+                    if count >= <Self as PortWord>::BITS {
+                        Self::ONES
+                    } else {
+                        (1 << count) - 1
+                    }
+                }
+
+                fn count_ones(self) -> u32 {
+                    <$word>::count_ones(self)
+                }
+
+                fn trailing_zeros(self) -> u32 {
+                    <$word>::trailing_zeros(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_port_word!(u8, u16, u32, u64);